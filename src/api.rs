@@ -0,0 +1,227 @@
+use crate::*;
+use reqwest::header::HeaderMap;
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// waits up to this long without asking when the rate limit is used up
+const MAX_SILENT_WAIT: u64 = 5 * 60;
+const MAX_RETRIES: usize = 3;
+// GitHub asks to wait at least a minute after a secondary rate limit without Retry-After
+const SECONDARY_LIMIT_WAIT: u64 = 60;
+// warn once the remaining requests drop below this
+const LOW_QUOTA: u64 = 50;
+
+static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+static LAST_RATE_LIMIT: Mutex<Option<RateLimit>> = Mutex::new(None);
+
+#[derive(Clone, Copy)]
+pub struct RateLimit {
+    pub limit: u64,
+    pub remaining: u64,
+    pub reset: u64, // unix timestamp
+}
+
+/// One client for every request, so connections are reused.
+pub fn client() -> &'static reqwest::Client {
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .user_agent("rust-github-client")
+            .build()
+            .expect("Failed to create the http client")
+    })
+}
+
+pub fn request(method: Method, url: &str) -> RequestBuilder {
+    client().request(method, url)
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_secs()).unwrap_or(0)
+}
+
+fn header_number(headers: &HeaderMap, names: &[&str]) -> Option<u64> {
+    names.iter()
+        .find_map(|name| headers.get(*name))
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+}
+
+/// Reads GitHub / Gitea (`X-RateLimit-*`) and GitLab (`RateLimit-*`) headers.
+pub fn read_rate_limit(headers: &HeaderMap) -> Option<RateLimit> {
+    Some(RateLimit {
+        limit: header_number(headers, &["x-ratelimit-limit", "ratelimit-limit"])?,
+        remaining: header_number(headers, &["x-ratelimit-remaining", "ratelimit-remaining"])?,
+        reset: header_number(headers, &["x-ratelimit-reset", "ratelimit-reset"]).unwrap_or(0),
+    })
+}
+
+fn record_rate_limit(rate_limit: RateLimit) {
+    let mut last = LAST_RATE_LIMIT.lock().unwrap();
+    let was_low = last.map(|last| last.remaining < LOW_QUOTA).unwrap_or(false);
+    if rate_limit.remaining < LOW_QUOTA && !was_low {
+        println!(
+            "{}: only {} of {} API requests left, resets {}",
+            "Warning".yellow().bold(),
+            rate_limit.remaining,
+            rate_limit.limit,
+            format_reset(rate_limit.reset),
+        );
+    }
+    *last = Some(rate_limit);
+}
+
+pub fn format_reset(reset: u64) -> String {
+    let seconds = reset.saturating_sub(now());
+    match seconds {
+        0 => "now".to_string(),
+        1..=59 => format!("in {} s", seconds),
+        _ => format!("in {} min", seconds.div_ceil(60)),
+    }
+}
+
+/// Waits `seconds` for the rate limit, asking first if it's longer than a few minutes.
+async fn wait_for_rate_limit(seconds: u64) -> bool {
+    if seconds > MAX_SILENT_WAIT
+        && !command_line::confirm(format!("The API rate limit is used up for the next {} min. Wait?", seconds.div_ceil(60)).as_str()) {
+        return false;
+    }
+    println!("Rate limit reached, waiting {} s...", seconds);
+    tokio::time::sleep(Duration::from_secs(seconds)).await;
+    true
+}
+
+/// GitHub answers a secondary rate limit with a plain 403, only the message tells it apart.
+fn is_secondary_rate_limit(body: &[u8]) -> bool {
+    let body = String::from_utf8_lossy(body).to_lowercase();
+    body.contains("secondary rate limit") || body.contains("abuse detection")
+}
+
+/// Puts a response back together after its body was read.
+fn rebuild_response(status: StatusCode, headers: HeaderMap, body: Vec<u8>) -> Response {
+    let mut response = http::Response::new(body);
+    *response.status_mut() = status;
+    *response.headers_mut() = headers;
+    Response::from(response)
+}
+
+/// Sends the request and handles rate limits: waits for `Retry-After`, for the reset of
+/// an exhausted quota and after a secondary rate limit, then retries.
+/// Every other response is returned as it is.
+pub async fn send(request: RequestBuilder) -> Result<Response, Box<dyn std::error::Error>> {
+    for attempt in 1..=MAX_RETRIES {
+        let retry = request.try_clone().ok_or("Request can't be retried")?;
+        let response = retry.send().await?;
+
+        let rate_limit = read_rate_limit(response.headers());
+        if let Some(rate_limit) = rate_limit {
+            record_rate_limit(rate_limit);
+        }
+
+        let status = response.status();
+        if status != StatusCode::FORBIDDEN && status != StatusCode::TOO_MANY_REQUESTS {
+            return Ok(response);
+        }
+
+        // secondary rate limits may send Retry-After, an exhausted quota only the reset time
+        let wait = match header_number(response.headers(), &["retry-after"]) {
+            Some(seconds) => seconds,
+            None => match rate_limit {
+                Some(rate_limit) if rate_limit.remaining == 0 => rate_limit.reset.saturating_sub(now()) + 1,
+                _ if status == StatusCode::TOO_MANY_REQUESTS => SECONDARY_LIMIT_WAIT,
+                _ => {
+                    let headers = response.headers().clone();
+                    let body = response.bytes().await?.to_vec();
+                    if !is_secondary_rate_limit(&body) {
+                        // an ordinary 403 (missing rights, wrong token)
+                        return Ok(rebuild_response(status, headers, body));
+                    }
+                    SECONDARY_LIMIT_WAIT
+                }
+            },
+        };
+
+        // waiting only makes sense if another request follows
+        if attempt == MAX_RETRIES {
+            break;
+        }
+        if !wait_for_rate_limit(wait).await {
+            return Err(format!("API rate limit exceeded, it resets {}", rate_limit.map(|rate_limit| format_reset(rate_limit.reset)).unwrap_or_else(|| "soon".to_string())).into());
+        }
+    }
+    Err(format!("API rate limit still exceeded after {} attempts, try again later", MAX_RETRIES).into())
+}
+
+/// Percent-encodes everything except unreserved characters, for paths and query values.
+pub fn encode(text: &str) -> String {
+    let mut encoded = String::new();
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// A multipart/form-data body with one file in `field`. Returns the content type (with the boundary) and the body.
+pub fn multipart_file(field: &str, file_name: &str, content: &[u8]) -> (String, Vec<u8>) {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_nanos()).unwrap_or(0);
+    let boundary = format!("git-manager-{:x}", nanos);
+    let file_name = file_name.replace('"', "%22");
+
+    let mut body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: application/octet-stream\r\n\r\n",
+        boundary, field, file_name,
+    ).into_bytes();
+    body.extend_from_slice(content);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+    (format!("multipart/form-data; boundary={}", boundary), body)
+}
+
+/// The url of the next page from the `Link` header, None on the last page.
+pub fn next_page(response: &Response) -> Option<String> {
+    response.headers().get(reqwest::header::LINK)
+        .and_then(|link| link.to_str().ok())
+        .and_then(next_page_url)
+}
+
+/// Finds the url marked with rel="next" in a Link header like `<url?page=2>; rel="next", <url?page=5>; rel="last"`.
+pub fn next_page_url(link_header: &str) -> Option<String> {
+    link_header.split(',').find_map(|part| {
+        let (url, params) = part.split_once(';')?;
+        let is_next = params.split(';').any(|param| param.trim() == "rel=\"next\"");
+        if !is_next {
+            return None;
+        }
+        Some(url.trim().trim_start_matches('<').trim_end_matches('>').to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_page_url_finds_the_next_link() {
+        let header = "<https://api.github.com/user/repos?page=2>; rel=\"next\", <https://api.github.com/user/repos?page=5>; rel=\"last\"";
+        assert_eq!(next_page_url(header).as_deref(), Some("https://api.github.com/user/repos?page=2"));
+
+        let header = "<https://example.com/?page=1>; rel=\"prev\", <https://example.com/?page=3>; rel=\"next\"";
+        assert_eq!(next_page_url(header).as_deref(), Some("https://example.com/?page=3"));
+    }
+
+    #[test]
+    fn is_secondary_rate_limit_reads_the_message() {
+        assert!(is_secondary_rate_limit(br#"{"message": "You have exceeded a secondary rate limit. Please wait a few minutes before you try again."}"#));
+        assert!(is_secondary_rate_limit(br#"{"message": "You have triggered an abuse detection mechanism."}"#));
+        assert!(!is_secondary_rate_limit(br#"{"message": "Resource not accessible by integration"}"#));
+    }
+
+    #[test]
+    fn next_page_url_is_none_on_the_last_page() {
+        assert_eq!(next_page_url(""), None);
+        assert_eq!(next_page_url("<https://example.com/?page=4>; rel=\"prev\", <https://example.com/?page=1>; rel=\"first\""), None);
+        assert_eq!(next_page_url("not a link header"), None);
+    }
+}
@@ -0,0 +1,245 @@
+use crate::*;
+use std::sync::{Arc, Mutex};
+use tokio::io::AsyncReadExt;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+// keeps progress lines from wrapping, which would break redrawing them
+const MAX_STATUS_LENGTH: usize = 60;
+
+/// The live view of a bulk operation: finished repositories scroll up as normal lines,
+/// below them there is one line per repository that is still running.
+struct Progress {
+    active: Vec<(String, String)>, // repo name, last status from git
+    drawn_lines: usize,
+    done: usize,
+    total: usize,
+}
+
+impl Progress {
+    fn new(total: usize) -> Self {
+        Progress { active: Vec::new(), drawn_lines: 0, done: 0, total }
+    }
+
+    fn update(&mut self, name: &str, status: &str) {
+        let status: String = status.chars().take(MAX_STATUS_LENGTH).collect();
+        match self.active.iter_mut().find(|(active, _)| active == name) {
+            Some(entry) => entry.1 = status,
+            None => self.active.push((name.to_string(), status)),
+        }
+        self.redraw(None);
+    }
+
+    fn finish(&mut self, name: &str, line: String) {
+        self.active.retain(|(active, _)| active != name);
+        self.done += 1;
+        self.redraw(Some(line));
+    }
+
+    fn redraw(&mut self, finished_line: Option<String>) {
+        let mut stdout = io::stdout().lock();
+        if self.drawn_lines > 0 {
+            // back to the first progress line and clear everything below
+            let _ = write!(stdout, "\x1B[{}A", self.drawn_lines);
+        }
+        let _ = write!(stdout, "\x1B[J");
+
+        if let Some(line) = finished_line {
+            let _ = writeln!(stdout, "{}", line);
+        }
+        for (name, status) in &self.active {
+            let _ = writeln!(stdout, "  {} {}", name.blue(), status.italic());
+        }
+        let _ = writeln!(stdout, "[{}/{}]", self.done, self.total);
+        self.drawn_lines = self.active.len() + 1;
+        let _ = stdout.flush();
+    }
+}
+
+pub struct CloneJob {
+    pub name: String,
+    pub url: String,
+    pub target: String,
+}
+
+enum Outcome {
+    Cloned,
+    Updated(usize), // number of new commits
+    UpToDate,
+    Skipped(String), // left untouched, with the reason
+}
+
+/// Clones all jobs with at most `jobs` git processes at the same time and prints a summary at the end.
+/// With `sync`, existing clones are fetched and fast-forwarded instead of failing.
+pub async fn clone_repos(repos: Vec<CloneJob>, jobs: usize, sync: bool) {
+    let total = repos.len();
+    let progress = Arc::new(Mutex::new(Progress::new(total)));
+    let semaphore = Arc::new(Semaphore::new(jobs.max(1)));
+    let mut tasks = JoinSet::new();
+
+    for repo in repos {
+        let progress = Arc::clone(&progress);
+        let semaphore = Arc::clone(&semaphore);
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            progress.lock().unwrap().update(&repo.name, "starting");
+
+            let result = if !Path::new(&repo.target).exists() {
+                run_clone(&repo, &progress).await.map(|_| Outcome::Cloned)
+            }
+            else if sync {
+                progress.lock().unwrap().update(&repo.name, "fetching");
+                let (target, url) = (repo.target.clone(), repo.url.clone());
+                tokio::task::spawn_blocking(move || sync_existing(&target, &url))
+                    .await
+                    .unwrap_or_else(|error| Err(error.to_string()))
+            }
+            else {
+                Err(format!("'{}' already exists (use --sync to update it)", repo.target))
+            };
+
+            let line = match &result {
+                Ok(Outcome::Cloned) => format!("{} {} cloned", "✓".green().bold(), repo.name),
+                Ok(Outcome::Updated(commits)) => format!("{} {} updated ({} new commit(s))", "✓".green().bold(), repo.name, commits),
+                Ok(Outcome::UpToDate) => format!("{} {} up to date", "✓".green().bold(), repo.name),
+                Ok(Outcome::Skipped(reason)) => format!("{} {} skipped: {}", "!".yellow().bold(), repo.name, reason.yellow()),
+                Err(error) => format!("{} {}: {}", "✗".red().bold(), repo.name, error.red()),
+            };
+            progress.lock().unwrap().finish(&repo.name, line);
+            (repo.name, result)
+        });
+    }
+
+    let (mut cloned, mut updated, mut up_to_date) = (0, 0, 0);
+    let mut skipped = Vec::new();
+    let mut failed = Vec::new();
+    while let Some(result) = tasks.join_next().await {
+        match result {
+            Ok((_, Ok(Outcome::Cloned))) => cloned += 1,
+            Ok((_, Ok(Outcome::Updated(_)))) => updated += 1,
+            Ok((_, Ok(Outcome::UpToDate))) => up_to_date += 1,
+            Ok((name, Ok(Outcome::Skipped(reason)))) => skipped.push((name, reason)),
+            Ok((name, Err(error))) => failed.push((name, error)),
+            Err(error) => failed.push(("?".to_string(), error.to_string())),
+        }
+    }
+
+    println!();
+    if sync {
+        println!("{} {} cloned, {} updated, {} up to date ({} repositories)", "Synced:".green().bold(), cloned, updated, up_to_date, total);
+    }
+    else {
+        println!("{} {} of {} repositories", "Cloned".green().bold(), cloned, total);
+    }
+    if !skipped.is_empty() {
+        println!("{} {}:", "Skipped".yellow().bold(), skipped.len());
+        for (name, reason) in skipped {
+            println!("  {}: {}", name.bold(), reason);
+        }
+    }
+    if !failed.is_empty() {
+        println!("{} {}:", "Failed".red().bold(), failed.len());
+        for (name, error) in failed {
+            println!("  {}: {}", name.bold(), error);
+        }
+    }
+}
+
+/// Fetches an existing clone and fast-forwards it if that's safe. Folders that belong to
+/// another repository, dirty trees and diverged branches are never touched.
+fn sync_existing(target: &str, url: &str) -> Result<Outcome, String> {
+    if !Path::new(target).join(".git").exists() {
+        return Ok(Outcome::Skipped("folder exists but is not a git repository".to_string()));
+    }
+
+    let origin = git_actions::run_git_checked(target, &["remote", "get-url", "origin"]).unwrap_or_default();
+    if !same_repository(&origin, url) {
+        let origin = if origin.is_empty() { "none" } else { origin.as_str() };
+        return Ok(Outcome::Skipped(format!("origin is {}, expected {}", origin, url)));
+    }
+
+    git_actions::run_git_checked(target, &["fetch", "origin"]).map_err(|error| format!("fetch failed: {}", error))?;
+
+    if !git_actions::git_succeeds(target, &["rev-parse", "--verify", "--quiet", "@{upstream}"]) {
+        return Ok(Outcome::Skipped("fetched, but the current branch has no upstream".to_string()));
+    }
+    let (ahead, behind) = git_actions::ahead_behind(target, "HEAD", "@{upstream}")
+        .ok_or("failed to compare with the upstream branch")?;
+    if behind == 0 {
+        return Ok(Outcome::UpToDate);
+    }
+    if ahead > 0 {
+        return Ok(Outcome::Skipped(format!("fetched, but diverged ({} local, {} remote commit(s))", ahead, behind)));
+    }
+    if git_actions::has_uncommitted_changes(target) {
+        return Ok(Outcome::Skipped("fetched, but has uncommitted changes".to_string()));
+    }
+
+    git_actions::run_git_checked(target, &["merge", "--ff-only", "@{upstream}"]).map_err(|error| format!("fast-forward failed: {}", error))?;
+    Ok(Outcome::Updated(behind))
+}
+
+/// Compares two remote urls by owner and name, so https and ssh urls of the same repository match.
+fn same_repository(first: &str, second: &str) -> bool {
+    match (git_actions::parse_full_name(first), git_actions::parse_full_name(second)) {
+        (Some(first), Some(second)) => first.0.eq_ignore_ascii_case(&second.0) && first.1.eq_ignore_ascii_case(&second.1),
+        _ => first.trim_end_matches(".git") == second.trim_end_matches(".git"),
+    }
+}
+
+/// Runs `git clone --progress` and forwards its progress output to the progress view.
+async fn run_clone(repo: &CloneJob, progress: &Arc<Mutex<Progress>>) -> Result<(), String> {
+    // lives until git is done, the credential file goes with it
+    let git_env = credentials::git_env();
+    let mut child = tokio::process::Command::new("git")
+        .args(["clone", "--progress", &repo.url, &repo.target])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .envs(git_env.vars())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|error| format!("failed to execute git: {}", error))?;
+
+    let mut stderr = child.stderr.take().ok_or("failed to read git output")?;
+    let mut buffer = [0u8; 1024];
+    // bytes, because a read can end in the middle of a character
+    let mut line = Vec::new();
+    let mut errors = Vec::new();
+
+    // git redraws its progress with \r, so both \r and \n end a line.
+    // they never occur inside a multi-byte character, so splitting the bytes on them is safe
+    loop {
+        let read = stderr.read(&mut buffer).await.unwrap_or(0);
+        if read == 0 {
+            break;
+        }
+        for &byte in &buffer[..read] {
+            if byte != b'\r' && byte != b'\n' {
+                line.push(byte);
+                continue;
+            }
+            let finished = String::from_utf8_lossy(&line).trim().to_string();
+            line.clear();
+            if finished.is_empty() {
+                continue;
+            }
+            if finished.starts_with("fatal:") || finished.starts_with("error:") {
+                errors.push(finished);
+            }
+            else {
+                progress.lock().unwrap().update(&repo.name, &finished);
+            }
+        }
+    }
+
+    let line = String::from_utf8_lossy(&line).trim().to_string();
+    if line.starts_with("fatal:") || line.starts_with("error:") {
+        errors.push(line);
+    }
+
+    let status = child.wait().await.map_err(|error| error.to_string())?;
+    if !status.success() {
+        return Err(errors.pop().unwrap_or_else(|| format!("git exited with {}", status)));
+    }
+    Ok(())
+}
@@ -22,17 +22,86 @@ pub fn input(prompt: &str) -> String {
     input.trim().to_string()
 }
 
+// like in `git commit --verbose`, the marker and everything below it is removed
+const SCISSORS: &str = "# ------------------------ >8 ------------------------";
+
+/// Asks for a longer text. With $EDITOR set the editor is opened on a temporary file,
+/// otherwise the text is read as one line. None if the editor failed.
+pub fn compose(prompt: &str) -> Option<String> {
+    let editor = match env::var("EDITOR").or_else(|_| env::var("VISUAL")) {
+        Ok(editor) if !editor.trim().is_empty() => editor,
+        _ => return Some(input(prompt)),
+    };
+
+    let path = env::temp_dir().join(format!("git-manager-{}.md", std::process::id()));
+    let template = format!("\n{}\n# {}\n# Do not modify or remove the line above, everything below it is ignored.\n", SCISSORS, prompt);
+    if let Err(error) = fs::write(&path, template) {
+        throw_error(format!("Failed to create a temporary file for {}: {}", editor, error).as_str());
+        return Some(input(prompt));
+    }
+
+    // through the shell, so editors with arguments like "code --wait" work
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", editor))
+        .arg("sh")
+        .arg(&path)
+        .status();
+    let text = fs::read_to_string(&path).unwrap_or_default();
+    let _ = fs::remove_file(&path);
+
+    match status {
+        Ok(status) if status.success() => {}
+        _ => {
+            throw_error(format!("{} did not exit successfully", editor).as_str());
+            return None;
+        }
+    }
+    let text = match text.lines().position(|line| line.trim_end() == SCISSORS) {
+        Some(position) => text.lines().take(position).collect::<Vec<&str>>().join("\n"),
+        None => text,
+    };
+    Some(text.trim().to_string())
+}
+
+/// Asks a yes/no question, everything except y / yes counts as no.
+pub fn confirm(prompt: &str) -> bool {
+    print!("{} {} ", prompt.yellow(), "(y/n)".bold());
+    let _ = io::stdout().flush();
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer).expect("Failed to read input.");
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
 pub fn print_in_file_infos() {
     new_lines(1);
     use colored::*;
 
     let commands_info = vec![
-        ("upload", "Uploads this repository directly"),
-        ("create branch <name>", "Creates a new branch"),
-        ("update", "Get the newest version of this project directly"),
+        ("upload (commit message) (--branch <name>)", "Commits all changes and pushes them (--force to force-with-lease)"),
+        ("create branch <name> (from <base>)", "Creates a new branch and pushes it"),
+        ("pr create (title) (--base <branch>)", "Opens a pull request from the current branch, in a fork against upstream (title defaults to the last commit)"),
+        ("pr list", "Shows the open pull requests and their checks"),
+        ("pr checkout <number>", "Fetches a pull request into a local branch and switches to it"),
+        ("pr merge <number>", "Merges a pull request (--squash / --rebase / --merge)"),
+        ("issue list", "Shows the open issues (--state open/closed/all, --assignee me, --label <name>)"),
+        ("issue show <number>", "Shows an issue with its comments"),
+        ("issue create <title> (--body <text>)", "Opens an issue, without --body the description is written in $EDITOR"),
+        ("issue comment <number> (text)", "Comments on an issue, without text $EDITOR is opened"),
+        ("issue close <number>", "Closes an issue"),
+        ("release create <tag>", "Tags HEAD if needed, pushes the tag and publishes a release (--asset <file> to upload files)"),
+        ("release list", "Shows the releases"),
+        ("release delete <tag>", "Deletes a release, and the tag if you want"),
+        ("repo set visibility <public / private>", "Changes who can see the repository"),
+        ("repo set description <text>", "Changes the description of the repository"),
+        ("repo set topics <a,b,c>", "Replaces the topics of the repository"),
+        ("repo rename <new name>", "Renames the repository, its origin url and the local folder"),
+        ("repo archive / unarchive", "Makes the repository read-only or writable again"),
+        ("sync fork", "Fast-forwards the default branch of the fork and your clone to upstream"),
+        ("update (--rebase / --merge) (--branch <name>)", "Fetch and fast-forward this project (--force resets to the remote)"),
         ("close / back", "Get back to the home state"),
         ("delete repo", "Deletes the repository from your account"),
-        ("delete branch <name>", "Deletes the branch of the project you are currently in"),
+        ("delete branch <name>", "Deletes the branch locally and on the forge (--local / --remote for only one)"),
         ("delete file", "Deletes the file of the project you are currently in"),
         ("exit / q", "Exit the Git-Manager"),
     ];
@@ -61,22 +130,34 @@ pub fn print_infos() {
     use colored::*;
     new_lines(1);
     let commands_info = vec![
-        ("upload <repo name>", "Upload a specific repository"),
+        ("upload <repo name> (commit message)", "Commit and push a specific repository (--branch <name>, --force)"),
         ("upload all", "Upload all repositories"),
-        ("create branch <branch name>", "Create a new branch (you have to open this repository first)"),
-        ("create repo <repo name>", "Create a new repository"),
-        ("delete repo <name>", "Deletes the repository from your Github account"),
-        ("delete branch <name> in <repo name>" , "Deletes branch in repository"),
-        ("delete folder <name>", "Deletes the file from your system"),
-        ("update <repo name>", "Get the newest version of a project"),
-        ("download all from <github name>", "Download all repositories from your account"),
-        ("download <repo name> from <github name>", "Download a repository from another user"),
-        ("migrate <project name>", "Migrate a non git project to git"),
+        ("create branch <name> in <repo name>", "Create a new branch and push it (from <base>, default is HEAD)"),
+        ("create repo <repo name> <public / private>", "Create a new repository on your account and push it from your project path"),
+        ("delete repo <name>", "Deletes the repository from your account"),
+        ("delete branch <name> from <repo name>" , "Deletes branch in repository (--local / --remote)"),
+        ("delete folder <name>", "Moves the folder into the trash"),
+        ("trash list", "Show the folders in the trash"),
+        ("trash restore <id>", "Move a folder back to where it was"),
+        ("trash empty", "Permanently delete everything in the trash"),
+        ("update <repo name>", "Get the newest version of a project (--rebase, --merge, --force)"),
+        ("download all from <user name>", "Download all repositories of a user (--jobs <n> clones in parallel)"),
+        ("download all from org <org name>", "Download all repositories of an organization (GitLab: group) you can see"),
+        ("download all ... --sync", "Clone missing repositories and fast-forward existing clones"),
+        ("download all ... --no-forks / --no-archived", "Leave out forks / archived repositories"),
+        ("download all ... --language <lang> / --topic <topic>", "Only repositories in that language / with that topic"),
+        ("download all ... --match / --exclude <glob>", "Only / never repositories whose name matches (* and ? wildcards)"),
+        ("download <repo name> from <user name>", "Download a repository from another user"),
+        ("fork <repo name> from <user name>", "Fork a repository to your account, clone it and add the original as upstream"),
+        ("download / fork ... --ssh / --https", "Clone over ssh or https instead of the configured clone protocol"),
+        ("migrate <project name> <public / private>", "Turn a non git project into a repository on your account"),
         ("list", "List all known git projects"),
+        ("list remote (user)", "List the repositories of you or another user on the forge"),
         ("open config", "Open the config file"),
         ("open <repo name>", "Open a specific repository"),
         ("close / back", "Get back to the home state"),
         ("exit / q", "Exit the Git-Manager"),
+        ("rate", "Show how many API requests you have left"),
         ("clear", "Clear the terminal"),
     ];
 
@@ -135,6 +216,15 @@ pub fn print_config_infos() {
         ("set username <name>", "Change your username"),
         ("set path <path>", "Change your project Path"),
         ("set key <key>", "Change your api key"),
+        ("set forge <github / gitlab / gitea / forgejo>", "Change which kind of forge the account is on"),
+        ("set api_url <url>", "Change the API url (https://<host>/api/v3 for GitHub Enterprise, /api/v4 for GitLab, /api/v1 for Gitea)"),
+        ("set web_url <url>", "Change the url repositories are cloned from (e.g. https://<host>)"),
+        ("set jobs <number>", "Change how many repositories are cloned at the same time"),
+        ("set protocol <https / ssh>", "Clone over https or with your ssh key"),
+        ("account save <name>", "Save the current forge, username, key and urls as an account"),
+        ("account use <name>", "Switch to a saved account"),
+        ("account list", "Show the saved accounts"),
+        ("account remove <name>", "Forget a saved account"),
         ("show", "display your current settings"),
         ("close / back", "Get back to the home state"),
         ("exit / q", "Exit the Git-Manager"),
@@ -162,13 +252,13 @@ pub fn print_config_infos() {
 }
 
 
-pub fn check_name(name: &String, error_msg: &str) -> bool {
+pub fn check_name(name: &str, error_msg: &str) -> bool {
     if name.trim() == ""{
         command_line::throw_error(error_msg);
-        return false;
+        false
     }
     else {
-        return true;
+        true
     }
 }
 
@@ -180,6 +270,6 @@ pub fn check_if_empty_and_print_info(item: &str, message: &str) -> bool{
         }
         return false;
     }
-    return true;
+    true
 }
 
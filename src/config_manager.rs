@@ -8,7 +8,7 @@ pub fn manage_config() -> Config{
 
     if path.exists() {
         // Read existing configuration from file
-        let file = File::open(&path).expect("Failed to open config file.");
+        let file = File::open(path).expect("Failed to open config file.");
         config = serde_json::from_reader(file).expect("Failed to parse config file.");
         config
     } else {
@@ -17,8 +17,14 @@ pub fn manage_config() -> Config{
             api_key: command_line::input("API Key: "),
             username: command_line::input("Username: "),
             project_path: command_line::input("Project path: "),
+            api_url: default_api_url(),
+            web_url: default_web_url(),
+            clone_jobs: default_clone_jobs(),
+            clone_protocol: default_clone_protocol(),
+            forge: default_forge(),
+            accounts: Vec::new(),
         };
-        write_to_json(&config_path, &config);
+        write_to_json(config_path, &config);
         // Write configuration to file
         //let serialized = serde_json::to_string(&config).expect("Failed to serialize config.");
         //let mut file = File::create(&path).expect("Failed to create config file.");
@@ -27,6 +33,87 @@ pub fn manage_config() -> Config{
     }
 }
 
+pub fn default_api_url() -> String {
+    "https://api.github.com".to_string()
+}
+
+pub fn default_web_url() -> String {
+    "https://github.com".to_string()
+}
+
+pub fn default_clone_jobs() -> usize {
+    4
+}
+
+pub fn default_clone_protocol() -> String {
+    "https".to_string()
+}
+
+pub fn default_forge() -> String {
+    "github".to_string()
+}
+
+/// Saves the current login (forge, username, key and urls) under `name`, replacing an account with the same name.
+pub fn save_account(config: &mut Config, name: &str) {
+    let account = Account {
+        name: name.to_string(),
+        forge: config.forge.clone(),
+        username: config.username.clone(),
+        api_key: config.api_key.clone(),
+        api_url: config.api_url.clone(),
+        web_url: config.web_url.clone(),
+    };
+    match config.accounts.iter_mut().find(|saved| saved.name.eq_ignore_ascii_case(name)) {
+        Some(saved) => *saved = account,
+        None => config.accounts.push(account),
+    }
+    write_to_json("./config.json", config);
+}
+
+/// Makes the saved account the active one. Returns false if there is no account with that name.
+pub fn use_account(config: &mut Config, name: &str) -> bool {
+    let account = match config.accounts.iter().find(|saved| saved.name.eq_ignore_ascii_case(name)) {
+        Some(account) => account.clone(),
+        None => return false,
+    };
+    config.forge = account.forge;
+    config.username = account.username;
+    config.api_key = account.api_key;
+    config.api_url = account.api_url;
+    config.web_url = account.web_url;
+    write_to_json("./config.json", config);
+    true
+}
+
+pub fn remove_account(config: &mut Config, name: &str) -> bool {
+    let count = config.accounts.len();
+    config.accounts.retain(|saved| !saved.name.eq_ignore_ascii_case(name));
+    if config.accounts.len() == count {
+        return false;
+    }
+    write_to_json("./config.json", config);
+    true
+}
+
+pub fn print_accounts(config: &Config) {
+    if config.accounts.is_empty() {
+        println!("No saved accounts, save the current one with 'account save <name>'");
+        return;
+    }
+    for account in &config.accounts {
+        let active = account.username == config.username && account.api_url == config.api_url && account.forge == config.forge;
+        let kind = forge::ForgeKind::parse(&account.forge).map(|kind| kind.name()).unwrap_or("?");
+        println!(
+            "{}  {}  {} @ {}{}",
+            account.name.blue().bold(),
+            kind,
+            account.username,
+            account.web_url,
+            if active { " (active)".green().bold().to_string() } else { String::new() },
+        );
+    }
+}
+
 pub fn write_to_json(file_path: &str, config: &Config) {
     // Convert the Config instance to JSON format
     let json = serde_json::to_string_pretty(config).expect("Failed to serialize config");
@@ -36,6 +123,7 @@ pub fn write_to_json(file_path: &str, config: &Config) {
         .read(true)
         .write(true)
         .create(true)
+        .truncate(true)
         .open(file_path)
         .expect("Failed to open file");
 
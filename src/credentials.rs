@@ -0,0 +1,91 @@
+use crate::*;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// a counter for the credential files, git processes of parallel clones need one each
+static NEXT_FILE: AtomicUsize = AtomicUsize::new(0);
+
+struct GitCredentials {
+    web_url: String,
+    username: String,
+    token: String,
+}
+
+static CREDENTIALS: Mutex<Option<GitCredentials>> = Mutex::new(None);
+
+/// Remembers the token for the git processes gm starts. Has to be called again when the config changes.
+pub fn set_from_config(user_config: &Config) {
+    let web_url = user_config.web_url.trim_end_matches('/');
+    let credentials = if user_config.api_key.trim().is_empty() || !web_url.starts_with("https://") {
+        None
+    }
+    else {
+        Some(GitCredentials {
+            web_url: web_url.to_string(),
+            username: user_config.username.clone(),
+            token: user_config.api_key.clone(),
+        })
+    };
+    *CREDENTIALS.lock().unwrap() = credentials;
+}
+
+/// Environment for one git process, keep it alive until the process has finished.
+/// The token is in a file only the user can read, removed again when this is dropped.
+pub struct GitEnv {
+    vars: Vec<(String, String)>,
+    file: Option<PathBuf>,
+}
+
+impl GitEnv {
+    pub fn vars(&self) -> Vec<(String, String)> {
+        self.vars.clone()
+    }
+}
+
+impl Drop for GitEnv {
+    fn drop(&mut self) {
+        if let Some(file) = &self.file {
+            let _ = fs::remove_file(file);
+        }
+    }
+}
+
+/// Environment for a git process: a temporary credential helper for the configured host that
+/// reads the token from a short-lived file. The token itself is never in the environment of git,
+/// its hooks or ssh, nothing is written to .git/config and the remote urls stay clean.
+pub fn git_env() -> GitEnv {
+    let credentials = CREDENTIALS.lock().unwrap();
+    let credentials = match credentials.as_ref() {
+        Some(credentials) => credentials,
+        None => return GitEnv { vars: Vec::new(), file: None },
+    };
+
+    let file = env::temp_dir().join(format!("git-manager-credentials-{}-{}", std::process::id(), NEXT_FILE.fetch_add(1, Ordering::Relaxed)));
+    if let Err(error) = write_private(&file, &format!("username={}\npassword={}\n", credentials.username, credentials.token)) {
+        command_line::throw_error(format!("Failed to hand the API token to git: {}", error).as_str());
+        return GitEnv { vars: Vec::new(), file: None };
+    }
+    // only answers 'get', git's 'store' and 'erase' are ignored so nothing gets saved
+    let helper = format!("!f() {{ test \"$1\" = get && cat '{}'; }}; f", file.to_string_lossy().replace('\'', "'\\''"));
+
+    // git reads GIT_CONFIG_KEY_<n> up to the count, so the user's own entries stay in front
+    let first = env::var("GIT_CONFIG_COUNT").ok().and_then(|count| count.trim().parse::<usize>().ok()).unwrap_or(0);
+    let key = format!("credential.{}.helper", credentials.web_url);
+    let vars = vec![
+        ("GIT_CONFIG_COUNT".to_string(), (first + 2).to_string()),
+        // an empty helper clears the ones from the user's config, so they can't prompt first
+        (format!("GIT_CONFIG_KEY_{}", first), key.clone()),
+        (format!("GIT_CONFIG_VALUE_{}", first), String::new()),
+        (format!("GIT_CONFIG_KEY_{}", first + 1), key),
+        (format!("GIT_CONFIG_VALUE_{}", first + 1), helper),
+    ];
+    GitEnv { vars, file: Some(file) }
+}
+
+fn write_private(path: &Path, content: &str) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(content.as_bytes())
+}
@@ -0,0 +1,416 @@
+use crate::*;
+
+/// A repository on the forge, the same no matter which backend it came from.
+#[derive(Deserialize)]
+pub struct RemoteRepository {
+    pub name: String,
+    pub full_name: String,
+    pub clone_url: String,
+    pub ssh_url: String,
+    pub html_url: String,
+    pub private: bool,
+    pub default_branch: String,
+    pub owner: Owner,
+    // only sent when the request is authenticated
+    #[serde(default)]
+    pub permissions: Option<Permissions>,
+    // the repository this one was forked from, only sent for single repositories
+    #[serde(default)]
+    pub parent: Option<Box<RemoteRepository>>,
+}
+
+#[derive(Deserialize)]
+pub struct Owner {
+    pub login: String,
+}
+
+#[derive(Deserialize)]
+pub struct Permissions {
+    pub admin: bool,
+    pub push: bool,
+    pub pull: bool,
+}
+
+impl RemoteRepository {
+    /// The url to use as origin, depending on the configured clone protocol.
+    pub fn remote_url(&self, user_config: &Config) -> &str {
+        if git_actions::use_ssh(&[], user_config) { &self.ssh_url } else { &self.clone_url }
+    }
+}
+
+/// A pull request, called merge request on GitLab.
+pub struct PullRequest {
+    pub number: u64,
+    pub title: String,
+    pub author: String,
+    pub head: String, // source branch
+    pub head_sha: String,
+    pub base: String, // target branch
+    pub url: String,
+    pub draft: bool,
+    pub checks: Checks,
+}
+
+/// The combined state of the CI checks on the head commit of a pull request.
+#[derive(PartialEq, Clone, Copy)]
+pub enum Checks {
+    Passing,
+    Failing,
+    Pending,
+    Missing, // nothing reported for the commit
+}
+
+impl Checks {
+    /// One failing check fails them all, otherwise a running one makes them pending.
+    pub fn combine(checks: impl IntoIterator<Item = Checks>) -> Checks {
+        let checks = checks.into_iter().collect::<Vec<Checks>>();
+        [Checks::Failing, Checks::Pending, Checks::Passing].into_iter()
+            .find(|state| checks.contains(state))
+            .unwrap_or(Checks::Missing)
+    }
+}
+
+/// The branch a pull request comes from.
+pub struct Head<'a> {
+    pub repo: &'a str, // full name, a fork or the repository of the pull request itself
+    pub branch: &'a str,
+}
+
+impl Head<'_> {
+    pub fn is_in(&self, owner: &str, name: &str) -> bool {
+        self.repo.eq_ignore_ascii_case(&format!("{}/{}", owner, name))
+    }
+
+    /// The head as GitHub and Gitea want it: the branch, or <owner>:<branch> from a fork.
+    pub fn qualified(&self, owner: &str, name: &str) -> String {
+        if self.is_in(owner, name) {
+            return self.branch.to_string();
+        }
+        let head_owner = self.repo.split('/').next().unwrap_or(self.repo);
+        format!("{}:{}", head_owner, self.branch)
+    }
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum MergeMethod {
+    Merge,
+    Squash,
+    Rebase,
+}
+
+impl MergeMethod {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MergeMethod::Merge => "merge",
+            MergeMethod::Squash => "squash",
+            MergeMethod::Rebase => "rebase",
+        }
+    }
+}
+
+pub struct Issue {
+    pub number: u64,
+    pub title: String,
+    pub body: String,
+    pub open: bool,
+    pub author: String,
+    pub labels: Vec<String>,
+    pub assignees: Vec<String>,
+    pub comments: u64,
+    pub url: String,
+    pub created_at: String,
+}
+
+pub struct Comment {
+    pub author: String,
+    pub body: String,
+    pub created_at: String,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum IssueState {
+    Open,
+    Closed,
+    All,
+}
+
+pub struct IssueFilter {
+    pub state: IssueState,
+    pub assignee: Option<String>, // username
+    pub labels: Vec<String>,      // all have to be set
+}
+
+pub struct Release {
+    pub id: u64, // GitLab addresses releases by their tag, there it's 0
+    pub tag: String,
+    pub name: String,
+    pub created_at: String,
+    pub draft: bool,
+    pub prerelease: bool,
+    pub assets: Vec<String>,
+    pub url: String,
+    pub upload_url: String, // only GitHub uploads assets to a separate url
+}
+
+/// Changes to the settings of a repository, None leaves a setting as it is.
+#[derive(Default)]
+pub struct RepoUpdate {
+    pub name: Option<String>,
+    pub private: Option<bool>,
+    pub description: Option<String>,
+    pub archived: Option<bool>,
+}
+
+/// Whose repositories to list.
+pub enum RepoOwner<'a> {
+    Own,             // the configured user, private repositories included
+    User(&'a str),
+    Group(&'a str),  // GitHub / Gitea organization, GitLab group
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum ForgeKind {
+    GitHub,
+    GitLab,
+    Gitea,
+    Forgejo, // a fork of Gitea with the same API
+}
+
+pub const FORGE_NAMES: &str = "github, gitlab, gitea, forgejo";
+
+impl ForgeKind {
+    pub fn parse(name: &str) -> Option<ForgeKind> {
+        match name.to_lowercase().as_str() {
+            "github" => Some(ForgeKind::GitHub),
+            "gitlab" => Some(ForgeKind::GitLab),
+            "gitea" => Some(ForgeKind::Gitea),
+            "forgejo" => Some(ForgeKind::Forgejo),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ForgeKind::GitHub => "GitHub",
+            ForgeKind::GitLab => "GitLab",
+            ForgeKind::Gitea => "Gitea",
+            ForgeKind::Forgejo => "Forgejo",
+        }
+    }
+}
+
+/// Everything gm needs from a forge. The clone url layout is the same on every supported forge.
+pub trait Forge {
+    fn user_config(&self) -> &Config;
+
+    async fn get_repository(&self, owner: &str, name: &str) -> Result<Option<RemoteRepository>, Box<dyn std::error::Error>>;
+    async fn create_repository(&self, name: &str, public: bool) -> Result<RemoteRepository, Box<dyn std::error::Error>>;
+    async fn delete_repository(&self, owner: &str, name: &str) -> Result<(), Box<dyn std::error::Error>>;
+    /// Forks `owner/name` into the account of the token. The fork may still be copied in the background.
+    async fn fork_repository(&self, owner: &str, name: &str) -> Result<RemoteRepository, Box<dyn std::error::Error>>;
+    /// Returns the repository as it is after the change, renamed ones under their new name.
+    async fn update_repository(&self, owner: &str, name: &str, update: &RepoUpdate) -> Result<RemoteRepository, Box<dyn std::error::Error>>;
+    /// Replaces all topics of the repository.
+    async fn set_topics(&self, owner: &str, name: &str, topics: &[String]) -> Result<(), Box<dyn std::error::Error>>;
+    async fn list_repositories(&self, owner: RepoOwner<'_>) -> Result<Vec<CloneData>, Box<dyn std::error::Error>>;
+    async fn branch_exists(&self, owner: &str, name: &str, branch: &str) -> Result<bool, Box<dyn std::error::Error>>;
+    async fn delete_branch(&self, owner: &str, name: &str, branch: &str) -> Result<(), Box<dyn std::error::Error>>;
+    async fn print_rate_limit(&self) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// Opens the pull request in `owner/name`, `head` can be a branch of a fork.
+    async fn create_pull_request(&self, owner: &str, name: &str, head: &Head<'_>, base: &str, title: &str, body: &str) -> Result<PullRequest, Box<dyn std::error::Error>>;
+    async fn get_pull_request(&self, owner: &str, name: &str, number: u64) -> Result<Option<PullRequest>, Box<dyn std::error::Error>>;
+    /// The open pull requests, with the state of their checks.
+    async fn list_pull_requests(&self, owner: &str, name: &str) -> Result<Vec<PullRequest>, Box<dyn std::error::Error>>;
+    async fn merge_pull_request(&self, owner: &str, name: &str, number: u64, method: MergeMethod) -> Result<(), Box<dyn std::error::Error>>;
+
+    async fn list_issues(&self, owner: &str, name: &str, filter: &IssueFilter) -> Result<Vec<Issue>, Box<dyn std::error::Error>>;
+    async fn get_issue(&self, owner: &str, name: &str, number: u64) -> Result<Option<Issue>, Box<dyn std::error::Error>>;
+    async fn list_comments(&self, owner: &str, name: &str, number: u64) -> Result<Vec<Comment>, Box<dyn std::error::Error>>;
+    async fn create_issue(&self, owner: &str, name: &str, title: &str, body: &str) -> Result<Issue, Box<dyn std::error::Error>>;
+    async fn comment_issue(&self, owner: &str, name: &str, number: u64, body: &str) -> Result<(), Box<dyn std::error::Error>>;
+    async fn close_issue(&self, owner: &str, name: &str, number: u64) -> Result<(), Box<dyn std::error::Error>>;
+
+    async fn create_release(&self, owner: &str, name: &str, tag: &str, title: &str, notes: &str) -> Result<Release, Box<dyn std::error::Error>>;
+    async fn get_release(&self, owner: &str, name: &str, tag: &str) -> Result<Option<Release>, Box<dyn std::error::Error>>;
+    async fn list_releases(&self, owner: &str, name: &str) -> Result<Vec<Release>, Box<dyn std::error::Error>>;
+    /// Deletes only the release, the tag stays.
+    async fn delete_release(&self, owner: &str, name: &str, release: &Release) -> Result<(), Box<dyn std::error::Error>>;
+    async fn upload_asset(&self, owner: &str, name: &str, release: &Release, file_name: &str, content: Vec<u8>) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// The ref the forge keeps the head of a pull request under, also for pull requests from forks.
+    fn pull_request_ref(&self, number: u64) -> String {
+        format!("refs/pull/{}/head", number)
+    }
+
+    /// https://<host>/<owner>/<name>.git or git@<host>:<owner>/<name>.git, with the host taken from web_url.
+    fn clone_url(&self, owner: &str, name: &str, ssh: bool) -> String {
+        let web_url = self.user_config().web_url.trim_end_matches('/');
+        if ssh {
+            let host = web_url.split_once("://").map(|(_, host)| host).unwrap_or(web_url);
+            format!("git@{}:{}/{}.git", host, owner, name)
+        }
+        else {
+            format!("{}/{}/{}.git", web_url, owner, name)
+        }
+    }
+}
+
+/// The forge of the active account, unknown names fall back to GitHub.
+pub fn kind(user_config: &Config) -> ForgeKind {
+    ForgeKind::parse(&user_config.forge).unwrap_or(ForgeKind::GitHub)
+}
+
+pub fn name(user_config: &Config) -> &'static str {
+    kind(user_config).name()
+}
+
+// runs `$call` on the backend of the active account
+macro_rules! dispatch {
+    ($user_config:expr, $forge:ident => $call:expr) => {
+        match kind($user_config) {
+            ForgeKind::GitHub => {
+                let $forge = github::GitHub::new($user_config);
+                $call
+            }
+            ForgeKind::GitLab => {
+                let $forge = gitlab::GitLab::new($user_config);
+                $call
+            }
+            ForgeKind::Gitea | ForgeKind::Forgejo => {
+                let $forge = gitea::Gitea::new($user_config);
+                $call
+            }
+        }
+    };
+}
+
+pub async fn get_repository(user_config: &Config, owner: &str, name: &str) -> Result<Option<RemoteRepository>, Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.get_repository(owner, name).await)
+}
+
+pub async fn repository_exists(user_config: &Config, owner: &str, name: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    Ok(get_repository(user_config, owner, name).await?.is_some())
+}
+
+pub async fn create_repository(user_config: &Config, name: &str, public: bool) -> Result<RemoteRepository, Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.create_repository(name, public).await)
+}
+
+pub async fn delete_repository(user_config: &Config, owner: &str, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.delete_repository(owner, name).await)
+}
+
+pub async fn fork_repository(user_config: &Config, owner: &str, name: &str) -> Result<RemoteRepository, Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.fork_repository(owner, name).await)
+}
+
+pub async fn update_repository(user_config: &Config, owner: &str, name: &str, update: &RepoUpdate) -> Result<RemoteRepository, Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.update_repository(owner, name, update).await)
+}
+
+pub async fn set_topics(user_config: &Config, owner: &str, name: &str, topics: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.set_topics(owner, name, topics).await)
+}
+
+pub async fn list_repositories(user_config: &Config, owner: RepoOwner<'_>) -> Result<Vec<CloneData>, Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.list_repositories(owner).await)
+}
+
+pub async fn branch_exists(user_config: &Config, owner: &str, name: &str, branch: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.branch_exists(owner, name, branch).await)
+}
+
+pub async fn delete_branch(user_config: &Config, owner: &str, name: &str, branch: &str) -> Result<(), Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.delete_branch(owner, name, branch).await)
+}
+
+pub async fn print_rate_limit(user_config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.print_rate_limit().await)
+}
+
+pub fn clone_url(user_config: &Config, owner: &str, name: &str, ssh: bool) -> String {
+    dispatch!(user_config, forge => forge.clone_url(owner, name, ssh))
+}
+
+pub async fn create_pull_request(user_config: &Config, owner: &str, name: &str, head: &Head<'_>, base: &str, title: &str, body: &str) -> Result<PullRequest, Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.create_pull_request(owner, name, head, base, title, body).await)
+}
+
+pub async fn get_pull_request(user_config: &Config, owner: &str, name: &str, number: u64) -> Result<Option<PullRequest>, Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.get_pull_request(owner, name, number).await)
+}
+
+pub async fn list_pull_requests(user_config: &Config, owner: &str, name: &str) -> Result<Vec<PullRequest>, Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.list_pull_requests(owner, name).await)
+}
+
+pub async fn merge_pull_request(user_config: &Config, owner: &str, name: &str, number: u64, method: MergeMethod) -> Result<(), Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.merge_pull_request(owner, name, number, method).await)
+}
+
+pub fn pull_request_ref(user_config: &Config, number: u64) -> String {
+    dispatch!(user_config, forge => forge.pull_request_ref(number))
+}
+
+pub async fn list_issues(user_config: &Config, owner: &str, name: &str, filter: &IssueFilter) -> Result<Vec<Issue>, Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.list_issues(owner, name, filter).await)
+}
+
+pub async fn get_issue(user_config: &Config, owner: &str, name: &str, number: u64) -> Result<Option<Issue>, Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.get_issue(owner, name, number).await)
+}
+
+pub async fn list_comments(user_config: &Config, owner: &str, name: &str, number: u64) -> Result<Vec<Comment>, Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.list_comments(owner, name, number).await)
+}
+
+pub async fn create_issue(user_config: &Config, owner: &str, name: &str, title: &str, body: &str) -> Result<Issue, Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.create_issue(owner, name, title, body).await)
+}
+
+pub async fn comment_issue(user_config: &Config, owner: &str, name: &str, number: u64, body: &str) -> Result<(), Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.comment_issue(owner, name, number, body).await)
+}
+
+pub async fn close_issue(user_config: &Config, owner: &str, name: &str, number: u64) -> Result<(), Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.close_issue(owner, name, number).await)
+}
+
+pub async fn create_release(user_config: &Config, owner: &str, name: &str, tag: &str, title: &str, notes: &str) -> Result<Release, Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.create_release(owner, name, tag, title, notes).await)
+}
+
+pub async fn get_release(user_config: &Config, owner: &str, name: &str, tag: &str) -> Result<Option<Release>, Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.get_release(owner, name, tag).await)
+}
+
+pub async fn list_releases(user_config: &Config, owner: &str, name: &str) -> Result<Vec<Release>, Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.list_releases(owner, name).await)
+}
+
+pub async fn delete_release(user_config: &Config, owner: &str, name: &str, release: &Release) -> Result<(), Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.delete_release(owner, name, release).await)
+}
+
+pub async fn upload_asset(user_config: &Config, owner: &str, name: &str, release: &Release, file_name: &str, content: Vec<u8>) -> Result<(), Box<dyn std::error::Error>> {
+    dispatch!(user_config, forge => forge.upload_asset(owner, name, release, file_name, content).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combine_prefers_failing_then_pending() {
+        assert!(Checks::combine([Checks::Passing, Checks::Failing, Checks::Pending]) == Checks::Failing);
+        assert!(Checks::combine([Checks::Passing, Checks::Pending]) == Checks::Pending);
+        assert!(Checks::combine([Checks::Passing, Checks::Missing]) == Checks::Passing);
+    }
+
+    #[test]
+    fn combine_without_checks_is_missing() {
+        assert!(Checks::combine([]) == Checks::Missing);
+        assert!(Checks::combine([Checks::Missing, Checks::Missing]) == Checks::Missing);
+    }
+}
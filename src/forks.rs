@@ -0,0 +1,177 @@
+use crate::*;
+use forge::RemoteRepository;
+use std::time::Duration;
+
+// the forge copies the git data of a new fork in the background
+const CLONE_ATTEMPTS: u32 = 5;
+const CLONE_RETRY_SECONDS: u64 = 5;
+
+/// Forks `owner/name` into your account, clones the fork into `path` and adds the original as `upstream`.
+/// Returns true if a clone was created.
+pub async fn fork(name: &str, owner: &str, path: &str, ssh: bool, user_config: &Config) -> bool {
+    if owner.eq_ignore_ascii_case(&user_config.username) {
+        command_line::throw_error(format!("'{}/{}' is your own repository, there is nothing to fork", owner, name).as_str());
+        return false;
+    }
+    let upstream = match forge::get_repository(user_config, owner, name).await {
+        Ok(Some(upstream)) => upstream,
+        Ok(None) => {
+            command_line::throw_error(format!("Repository '{}/{}' not found on {}", owner, name, forge::name(user_config)).as_str());
+            return false;
+        }
+        Err(error) => {
+            command_line::throw_error(format!("Failed to look up '{}/{}': {}", owner, name, error).as_str());
+            return false;
+        }
+    };
+
+    let fork = match forge::fork_repository(user_config, owner, name).await {
+        Ok(fork) => fork,
+        Err(error) => {
+            command_line::throw_error(format!("Failed to fork '{}': {}", upstream.full_name, error).as_str());
+            return false;
+        }
+    };
+    println!("{} '{}' to '{}'", "Forked".green().bold(), upstream.full_name, fork.full_name);
+
+    // the fork can get another name if you already have a repository with that name
+    let target = Path::new(path).join(&fork.name);
+    if target.exists() {
+        command_line::throw_error(format!("'{}' already exists, the fork was not cloned", target.display()).as_str());
+        return false;
+    }
+    let target = target.to_string_lossy().to_string();
+    if !clone_fork(&fork, path, &target, ssh).await {
+        return false;
+    }
+
+    let upstream_url = if ssh { &upstream.ssh_url } else { &upstream.clone_url };
+    if let Err(error) = run_git_checked(&target, &["remote", "add", "upstream", upstream_url]) {
+        command_line::throw_error(format!("Failed to add the upstream remote, add it by hand: {}", error).as_str());
+        return true;
+    }
+    println!("{} the fork to '{}', upstream is {}", "Cloned".green().bold(), target, upstream_url);
+    true
+}
+
+async fn clone_fork(fork: &RemoteRepository, path: &str, target: &str, ssh: bool) -> bool {
+    let url = if ssh { &fork.ssh_url } else { &fork.clone_url };
+    for attempt in 1..=CLONE_ATTEMPTS {
+        match run_git_checked(path, &["clone", url, target]) {
+            Ok(_) => return true,
+            Err(error) if attempt == CLONE_ATTEMPTS => {
+                command_line::throw_error(format!("Failed to clone the fork, try 'download {} from {}' later: {}", fork.name, fork.owner.login, describe_git_error(&error)).as_str());
+            }
+            Err(_) => {
+                println!("{}", "The fork is not ready yet, waiting...".italic());
+                tokio::time::sleep(Duration::from_secs(CLONE_RETRY_SECONDS)).await;
+            }
+        }
+    }
+    false
+}
+
+/// Brings the default branch of the fork and of the local clone up to date with upstream.
+/// Only fast-forwards are done, a branch with commits of its own is reported and left alone.
+pub async fn sync(repoPath: &str, user_config: &Config) {
+    let repoName = Path::new(repoPath).file_name().and_then(|name| name.to_str()).unwrap_or(repoPath);
+    let (owner, name) = remote_full_name(Some(repoPath), repoName, user_config);
+
+    let fork = match forge::get_repository(user_config, &owner, &name).await {
+        Ok(Some(fork)) => fork,
+        Ok(None) => {
+            command_line::throw_error(format!("Repository '{}/{}' not found on {}", owner, name, forge::name(user_config)).as_str());
+            return;
+        }
+        Err(error) => {
+            command_line::throw_error(format!("Failed to look up '{}/{}': {}", owner, name, error).as_str());
+            return;
+        }
+    };
+
+    if !git_succeeds(repoPath, &["remote", "get-url", "upstream"]) {
+        match &fork.parent {
+            Some(parent) => {
+                if let Err(error) = run_git_checked(repoPath, &["remote", "add", "upstream", parent.remote_url(user_config)]) {
+                    command_line::throw_error(format!("Failed to add the upstream remote: {}", error).as_str());
+                    return;
+                }
+                println!("{} upstream {}", "Added".green().bold(), parent.remote_url(user_config));
+            }
+            None => {
+                command_line::throw_error(format!("'{}' is not a fork and has no 'upstream' remote (git remote add upstream <url>)", fork.full_name).as_str());
+                return;
+            }
+        }
+    }
+    // an upstream added by hand falls back to the branch name of the fork
+    let branch = fork.parent.as_ref().map(|parent| parent.default_branch.clone()).unwrap_or(fork.default_branch.clone());
+    let upstream_name = fork.parent.as_ref().map(|parent| parent.full_name.clone()).unwrap_or("upstream".to_string());
+
+    for remote in ["upstream", "origin"] {
+        if let Err(error) = run_git_checked(repoPath, &["fetch", remote]) {
+            command_line::throw_error(format!("Failed to fetch {}: {}", remote, describe_git_error(&error)).as_str());
+            return;
+        }
+    }
+    let upstream_ref = format!("refs/remotes/upstream/{}", branch);
+    let origin_ref = format!("refs/remotes/origin/{}", branch);
+    let (ahead, behind) = match ahead_behind(repoPath, &origin_ref, &upstream_ref) {
+        Some(counts) => counts,
+        None => {
+            command_line::throw_error(format!("'{}' is missing on origin or upstream", branch).as_str());
+            return;
+        }
+    };
+
+    if behind == 0 {
+        println!("The fork's {} is up to date with {}", branch, upstream_name);
+    }
+    else if ahead > 0 {
+        command_line::throw_error(format!("The fork's {} has {} commit(s) that {} doesn't have, it can't be fast-forwarded", branch, ahead, upstream_name).as_str());
+    }
+    else {
+        match run_git_checked(repoPath, &["push", "origin", &format!("{}:refs/heads/{}", upstream_ref, branch)]) {
+            Ok(_) => println!("{} the fork's {} by {} commit(s)", "Fast-forwarded".green().bold(), branch, behind),
+            Err(error) => command_line::throw_error(format!("Failed to push {} to the fork: {}", branch, describe_git_error(&error)).as_str()),
+        }
+    }
+
+    sync_local_branch(repoPath, &branch, &upstream_ref);
+
+    match ahead_behind(repoPath, &origin_ref, &upstream_ref) {
+        Some((ahead, behind)) => println!("{} is {} commit(s) ahead and {} behind {}", fork.full_name.blue().bold(), ahead, behind, upstream_name),
+        None => command_line::throw_error("Could not compare the fork with upstream"),
+    }
+}
+
+fn sync_local_branch(repoPath: &str, branch: &str, upstream_ref: &str) {
+    let local_ref = format!("refs/heads/{}", branch);
+    if !git_succeeds(repoPath, &["rev-parse", "--verify", "--quiet", &local_ref]) {
+        return;
+    }
+    let (ahead, behind) = ahead_behind(repoPath, &local_ref, upstream_ref).unwrap_or((0, 0));
+    if behind == 0 {
+        return;
+    }
+    if ahead > 0 {
+        command_line::throw_error(format!("Your local {} has {} commit(s) that upstream doesn't have, it was not updated", branch, ahead).as_str());
+        return;
+    }
+
+    // fetching into the checked out branch isn't allowed, so that one is merged instead
+    let result = if current_branch(repoPath).as_deref() == Some(branch) {
+        if has_uncommitted_changes(repoPath) {
+            command_line::throw_error(format!("Your local {} has uncommitted changes, it was not updated", branch).as_str());
+            return;
+        }
+        run_git_checked(repoPath, &["merge", "--ff-only", upstream_ref])
+    }
+    else {
+        run_git_checked(repoPath, &["fetch", ".", &format!("{}:{}", upstream_ref, local_ref)])
+    };
+    match result {
+        Ok(_) => println!("{} your local {} by {} commit(s)", "Fast-forwarded".green().bold(), branch, behind),
+        Err(error) => command_line::throw_error(format!("Failed to update your local {}: {}", branch, describe_git_error(&error)).as_str()),
+    }
+}
@@ -1,60 +1,771 @@
 use crate::*;
 
-pub fn delete_repo(repoName: &String, api_key: &String){
-    println!("The user wants to delete the repo {}", repoName);
+/// Deletes the repository from the forge after checking it belongs to the configured user.
+/// Returns true if it was deleted.
+pub async fn delete_repo(repoName: &str, localPath: Option<&str>, user_config: &Config) -> bool {
+    let (owner, name) = remote_full_name(localPath, repoName, user_config);
+    let full_name = format!("{}/{}", owner, name);
+
+    let remote = match forge::get_repository(user_config, &owner, &name).await {
+        Ok(Some(remote)) => remote,
+        Ok(None) => {
+            command_line::throw_error(format!("Repository '{}' not found on {} (or your token can't see it)", full_name, forge::name(user_config)).as_str());
+            return false;
+        }
+        Err(error) => {
+            command_line::throw_error(format!("Failed to look up '{}': {}", full_name, error).as_str());
+            return false;
+        }
+    };
+
+    if !remote.owner.login.eq_ignore_ascii_case(&user_config.username) {
+        command_line::throw_error(format!("'{}' belongs to '{}', not to you ('{}')", remote.full_name, remote.owner.login, user_config.username).as_str());
+        return false;
+    }
+    if let Some(permissions) = &remote.permissions {
+        if !permissions.admin {
+            command_line::throw_error(format!("You don't have admin rights on '{}'", remote.full_name).as_str());
+            return false;
+        }
+    }
+
+    println!("{}", format!("This permanently deletes '{}' from {}, including issues, pull requests and releases.", remote.full_name, forge::name(user_config)).red().bold());
+    let typed = command_line::input(format!("Type '{}' to confirm:", remote.full_name).as_str());
+    if typed != remote.full_name {
+        println!("Names don't match, '{}' was not deleted", remote.full_name);
+        return false;
+    }
+
+    if let Err(error) = forge::delete_repository(user_config, &remote.owner.login, &remote.name).await {
+        command_line::throw_error(format!("Failed to delete '{}': {}", remote.full_name, error).as_str());
+        return false;
+    }
+    println!("{} '{}' from {}", "Deleted".green().bold(), remote.full_name, forge::name(user_config));
+
+    if let Some(path) = localPath {
+        if command_line::confirm(format!("Also delete the local clone at '{}'?", path).as_str()) {
+            deleteDir(path);
+        }
+    }
+    true
+}
+
+/// Gets `(owner, name)` out of a https or ssh remote url.
+pub fn parse_full_name(url: &str) -> Option<(String, String)> {
+    let url = url.trim().trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+
+    // git@host:owner/name or https://host/owner/name
+    let path = match url.split_once("://") {
+        Some((_, rest)) => rest.split_once('/')?.1,
+        None => url.split_once(':')?.1,
+    };
+
+    // GitLab groups can be nested, so the owner is everything in front of the name
+    let (owner, name) = path.rsplit_once('/')?;
+    if owner.is_empty() || name.is_empty() {
+        return None;
+    }
+    Some((owner.to_string(), name.to_string()))
 }
 
-pub fn delete_branch(repoName: &String, branchName: &String, api_key: &String){
-    println!("the user wants to delete the branch {} from the repo {}", branchName, repoName);
+/// Deletes a branch locally and/or on the forge. The default branch and the checked out branch are never deleted.
+/// Deleting only locally doesn't need the forge, the default branch is taken from origin/HEAD then.
+pub async fn delete_branch(repoPath: &str, branchName: &str, local: bool, remote: bool, user_config: &Config){
+    let repoName = Path::new(repoPath).file_name().and_then(|name| name.to_str()).unwrap_or(repoPath);
+    let (owner, name) = remote_full_name(Some(repoPath), repoName, user_config);
+    let full_name = format!("{}/{}", owner, name);
+
+    let default_branch = if remote {
+        match forge::get_repository(user_config, &owner, &name).await {
+            Ok(Some(remote_repo)) => remote_repo.default_branch,
+            Ok(None) => {
+                command_line::throw_error(format!("Repository '{}' not found on {} (or your token can't see it)", full_name, forge::name(user_config)).as_str());
+                return;
+            }
+            Err(error) => {
+                command_line::throw_error(format!("Failed to look up '{}': {}", full_name, error).as_str());
+                return;
+            }
+        }
+    }
+    else {
+        match run_git_checked(repoPath, &["symbolic-ref", "--short", "refs/remotes/origin/HEAD"]) {
+            Ok(head) => head.trim_start_matches("origin/").to_string(),
+            Err(_) => {
+                command_line::throw_error(format!("Could not find the default branch of '{}', set it with 'git remote set-head origin --auto'", repoName).as_str());
+                return;
+            }
+        }
+    };
+
+    if branchName == default_branch {
+        command_line::throw_error(format!("'{}' is the default branch of '{}' and can't be deleted", branchName, full_name).as_str());
+        return;
+    }
+    if current_branch(repoPath).as_deref() == Some(branchName) {
+        command_line::throw_error(format!("'{}' is currently checked out in '{}', switch to another branch first", branchName, repoName).as_str());
+        return;
+    }
+
+    let exists_locally = git_succeeds(repoPath, &["rev-parse", "--verify", "--quiet", &format!("refs/heads/{}", branchName)]);
+    let exists_remotely = if remote {
+        match forge::branch_exists(user_config, &owner, &name, branchName).await {
+            Ok(exists) => exists,
+            Err(error) => {
+                command_line::throw_error(format!("Failed to check the branches of '{}': {}", full_name, error).as_str());
+                return;
+            }
+        }
+    }
+    else {
+        false
+    };
+
+    let delete_local = local && exists_locally;
+    let delete_remote = remote && exists_remotely;
+    if !delete_local && !delete_remote {
+        command_line::throw_error(format!("Branch '{}' not found {}", branchName, match (local, remote) {
+            (true, false) => "locally",
+            (false, true) => "on the remote",
+            _ => "locally or on the remote",
+        }).as_str());
+        return;
+    }
+
+    // compare against the newest state of the default branch, if that fails the branch counts as unmerged.
+    // a local delete goes by what was fetched last, so it works offline
+    let branch_ref = if exists_locally { branchName.to_string() } else { format!("origin/{}", branchName) };
+    let range = format!("origin/{}..{}", default_branch, branch_ref);
+    let fetched = if remote { run_git_checked(repoPath, &["fetch", "origin"]).map(|_| ()) } else { Ok(()) };
+    let unmerged = fetched
+        .map_err(|error| describe_git_error(&error))
+        .and_then(|_| run_git_checked(repoPath, &["rev-list", "--count", &range]))
+        .and_then(|count| count.parse::<usize>().map_err(|error| error.to_string()));
+    let question = match unmerged {
+        Ok(0) => None,
+        Ok(unmerged) => Some(format!("'{}' has {} commit(s) that are not merged into '{}'. Delete it anyway?", branchName, unmerged, default_branch)),
+        Err(error) => Some(format!("Could not check if '{}' is merged into '{}' ({}). Delete it anyway?", branchName, default_branch, error)),
+    };
+    if let Some(question) = question {
+        if !command_line::confirm(question.as_str()) {
+            println!("Branch '{}' was not deleted", branchName);
+            return;
+        }
+    }
+
+    if delete_local {
+        match run_git_checked(repoPath, &["branch", "-D", branchName]) {
+            Ok(_) => println!("{} local branch '{}' in '{}'", "Deleted".green().bold(), branchName, repoName),
+            Err(error) => command_line::throw_error(format!("Failed to delete local branch '{}': {}", branchName, error).as_str()),
+        }
+    }
+    if delete_remote {
+        match forge::delete_branch(user_config, &owner, &name, branchName).await {
+            Ok(_) => {
+                let _ = run_git(repoPath, &["branch", "-d", "-r", &format!("origin/{}", branchName)]);
+                println!("{} branch '{}' on '{}'", "Deleted".green().bold(), branchName, full_name);
+            }
+            Err(error) => command_line::throw_error(format!("Failed to delete branch '{}' on '{}': {}", branchName, full_name, error).as_str()),
+        }
+    }
 }
 
-pub fn create_repo(repoName: &String, public: &bool, path: &String, api_key: &String){
-    println!("the user wants to create a repo:");
-    println!("Name: {}", repoName);
-    println!("Path (wo das repo ist, Optional): {}", path);
-    println!("Public: {}", public);
-    println!("API-Key: {}", api_key);
+pub async fn create_repo(repoName: &str, public: &bool, path: &str, user_config: &Config){
+    let repoPath = Path::new(path).join(repoName);
+    let repoPath = repoPath.to_string_lossy().to_string();
+
+    // reuse an existing local repository, but never overwrite an origin that is already set
+    if Path::new(&repoPath).join(".git").exists() && git_succeeds(&repoPath, &["remote", "get-url", "origin"]) {
+        command_line::throw_error(format!("'{}' already has an 'origin' remote", repoPath).as_str());
+        return;
+    }
+
+    // set up locally first, so a broken folder doesn't leave an empty repository on the forge
+    if let Err(error) = init_local_repo(&repoPath, repoName) {
+        command_line::throw_error(format!("Failed to set up the local repository at '{}': {}", repoPath, error).as_str());
+        return;
+    }
+
+    let remote = match forge::create_repository(user_config, repoName, *public).await {
+        Ok(remote) => remote,
+        Err(error) => {
+            command_line::throw_error(format!("Failed to create repository '{}': {}", repoName, error).as_str());
+            return;
+        }
+    };
+    println!("{} {} ({})", "Created".green().bold(), remote.full_name, if remote.private { "private" } else { "public" });
+
+    let push = run_git_checked(&repoPath, &["remote", "add", "origin", remote.remote_url(user_config)])
+        .and_then(|_| run_git_checked(&repoPath, &["push", "--set-upstream", "origin", "HEAD"]));
+    if let Err(error) = push {
+        command_line::throw_error(format!("Failed to push '{}': {}", repoName, describe_git_error(&error)).as_str());
+        // the remote is still empty, so removing it loses nothing
+        if !command_line::confirm(format!("Delete the empty '{}' from {} again?", remote.full_name, forge::name(user_config)).as_str()) {
+            println!("'{}' was kept at {}, push to it with 'upload'", remote.full_name, remote.html_url);
+            return;
+        }
+        let _ = run_git(&repoPath, &["remote", "remove", "origin"]);
+        match forge::delete_repository(user_config, &remote.owner.login, &remote.name).await {
+            Ok(()) => println!("{} '{}'", "Deleted".green().bold(), remote.full_name),
+            Err(error) => command_line::throw_error(format!("Could not remove '{}' from {} again, delete it by hand at {}: {}", remote.full_name, forge::name(user_config), remote.html_url, error).as_str()),
+        }
+        return;
+    }
+    println!("{} '{}' to {}", "Uploaded".green().bold(), repoPath, remote.html_url);
 }
 
-pub fn create_branch(repoName: &String, branchName: &String, api_key: &String){
-    println!("the user wants to create a branch in {} with the name {} -> api key: {}", repoName, branchName, api_key);
+/// Makes sure `repoPath` is a git repository with at least one commit, creating a README if it is empty.
+fn init_local_repo(repoPath: &str, repoName: &str) -> Result<(), String> {
+    ensure_path_exists(repoPath).map_err(|error| error.to_string())?;
+
+    if !Path::new(repoPath).join(".git").exists() {
+        run_git_checked(repoPath, &["init", "--initial-branch=main"])?;
+    }
+
+    if git_succeeds(repoPath, &["rev-parse", "--verify", "--quiet", "HEAD"]) {
+        return Ok(());
+    }
+
+    let is_empty = fs::read_dir(repoPath)
+        .map(|entries| entries.flatten().all(|entry| entry.file_name() == ".git"))
+        .unwrap_or(false);
+    if is_empty {
+        fs::write(Path::new(repoPath).join("README.md"), format!("# {}\n", repoName)).map_err(|error| error.to_string())?;
+    }
+
+    run_git_checked(repoPath, &["add", "--all"])?;
+    run_git_checked(repoPath, &["commit", "--allow-empty", "-m", "Initial commit"])?;
+    Ok(())
 }
 
-pub fn upload(repoPath: &String, commitMessage: &String, force: bool, branch_name: String){
-    println!("the user wants to upload: {}", repoPath);
-    println!("commit msg: {}", commitMessage);
-    println!("Force: {}", force);
-    
+pub async fn create_branch(repoPath: &str, branchName: &str, base: Option<&str>, user_config: &Config){
+    let repoName = Path::new(repoPath).file_name().and_then(|name| name.to_str()).unwrap_or(repoPath);
+    let base = base.unwrap_or("HEAD");
+
+    if !git_succeeds(repoPath, &["check-ref-format", "--branch", branchName]) {
+        command_line::throw_error(format!("'{}' is not a valid branch name", branchName).as_str());
+        return;
+    }
+    let base_commit = format!("{}^{{commit}}", base);
+    if !git_succeeds(repoPath, &["rev-parse", "--verify", "--quiet", &base_commit]) {
+        command_line::throw_error(format!("Base '{}' does not exist in '{}'", base, repoName).as_str());
+        return;
+    }
+    if git_succeeds(repoPath, &["rev-parse", "--verify", "--quiet", &format!("refs/heads/{}", branchName)]) {
+        command_line::throw_error(format!("Branch '{}' already exists locally in '{}'", branchName, repoName).as_str());
+        return;
+    }
+
+    let (owner, name) = remote_full_name(Some(repoPath), repoName, user_config);
+    let full_name = format!("{}/{}", owner, name);
+    match forge::get_repository(user_config, &owner, &name).await {
+        Ok(Some(remote)) => {
+            if let Some(permissions) = remote.permissions {
+                if !permissions.push {
+                    command_line::throw_error(format!("Your token has no push rights on '{}'", full_name).as_str());
+                    return;
+                }
+            }
+        }
+        Ok(None) => {
+            command_line::throw_error(format!("Repository '{}' not found on {} (or your token can't see it)", full_name, forge::name(user_config)).as_str());
+            return;
+        }
+        Err(error) => {
+            command_line::throw_error(format!("Failed to look up '{}': {}", full_name, error).as_str());
+            return;
+        }
+    }
+    match forge::branch_exists(user_config, &owner, &name, branchName).await {
+        Ok(false) => {}
+        Ok(true) => {
+            command_line::throw_error(format!("Branch '{}' already exists on '{}'", branchName, full_name).as_str());
+            return;
+        }
+        Err(error) => {
+            command_line::throw_error(format!("Failed to check the branches of '{}': {}", full_name, error).as_str());
+            return;
+        }
+    }
+
+    if let Err(error) = run_git_checked(repoPath, &["branch", "--no-track", branchName, base]) {
+        command_line::throw_error(format!("Failed to create branch '{}': {}", branchName, error).as_str());
+        return;
+    }
+    if let Err(error) = run_git_checked(repoPath, &["push", "--set-upstream", "origin", branchName]) {
+        // don't leave a local branch behind that never made it to the remote
+        let _ = run_git(repoPath, &["branch", "-D", branchName]);
+        command_line::throw_error(format!("Failed to push branch '{}': {}", branchName, describe_git_error(&error)).as_str());
+        return;
+    }
+
+    println!("{} branch '{}' from {} in '{}', tracking origin/{}", "Created".green().bold(), branchName, base, repoName, branchName);
 }
 
-pub fn update(repoPath: &String, force: bool, branch_name: String){
-    println!("the user wants to update: {}", repoPath);
-    println!("force: {}", force);
+/// Finds `(owner, name)` of a repository on the forge, using the origin of the local clone if there is one
+/// and the configured username otherwise.
+pub fn remote_full_name(localPath: Option<&str>, repoName: &str, user_config: &Config) -> (String, String) {
+    localPath
+        .and_then(|path| run_git_checked(path, &["remote", "get-url", "origin"]).ok())
+        .and_then(|url| parse_full_name(&url))
+        .unwrap_or_else(|| (user_config.username.clone(), repoName.to_string()))
 }
 
-pub fn download(repo_name: &String, username: &String, path: &str) {
-    let clone_url = format!("https://github.com/{}/{}.git", username, repo_name);
-    let target_path = path;
-    let output = Command::new("git")
-    .arg("clone")
-    .arg(&clone_url)
-    .arg(format!("{}/{}", target_path, extract_repo_name(&clone_url)))
-    .output()
-    .expect("Failed to execute git command");
+pub fn upload(repoPath: &str, commitMessage: &str, force: bool, branch_name: Option<String>){
+    let repoName = Path::new(repoPath).file_name().and_then(|name| name.to_str()).unwrap_or(repoPath);
+
+    let branch_name = match branch_name.or_else(|| current_branch(repoPath)) {
+        Some(branch) => branch,
+        None => {
+            command_line::throw_error(format!("Could not find out which branch '{}' is on (detached HEAD?), use --branch <name>", repoName).as_str());
+            return;
+        }
+    };
 
+    // stage everything in the working tree
+    let output = match run_git(repoPath, &["add", "--all"]) {
+        Ok(output) => output,
+        Err(error) => {
+            command_line::throw_error(format!("Failed to execute git in '{}': {}", repoPath, error).as_str());
+            return;
+        }
+    };
     if !output.status.success() {
-        eprintln!("Failed to clone repo: {}", clone_url);
-    } else {
-        println!("Successfully cloned: {}", clone_url);
+        command_line::throw_error(format!("Failed to stage changes in '{}': {}", repoName, git_stderr(&output)).as_str());
+        return;
+    }
+
+    // 'git diff --cached --quiet' exits with 1 if something is staged, anything else but 0 is an error
+    let has_changes = match run_git(repoPath, &["diff", "--cached", "--quiet"]) {
+        Ok(output) => match output.status.code() {
+            Some(0) => false,
+            Some(1) => true,
+            _ => {
+                command_line::throw_error(format!("Failed to check for staged changes in '{}': {}", repoName, git_stderr(&output)).as_str());
+                return;
+            }
+        },
+        Err(error) => {
+            command_line::throw_error(format!("Failed to execute git in '{}': {}", repoPath, error).as_str());
+            return;
+        }
+    };
+
+    if has_changes {
+        let output = match run_git(repoPath, &["commit", "-m", commitMessage]) {
+            Ok(output) => output,
+            Err(error) => {
+                command_line::throw_error(format!("Failed to execute git in '{}': {}", repoPath, error).as_str());
+                return;
+            }
+        };
+        if !output.status.success() {
+            command_line::throw_error(format!("Failed to commit in '{}': {}", repoName, git_stderr(&output)).as_str());
+            return;
+        }
+    }
+    else {
+        println!("{}: nothing to commit in '{}'", "Info".yellow().bold(), repoName);
+    }
+
+    // push the current HEAD to the requested branch, --force only overwrites if nobody else pushed in the meantime
+    let refspec = format!("HEAD:refs/heads/{}", branch_name);
+    let mut push_args = vec!["push", "origin", refspec.as_str()];
+    if force {
+        push_args.push("--force-with-lease");
+    }
+
+    let output = match run_git(repoPath, &push_args) {
+        Ok(output) => output,
+        Err(error) => {
+            command_line::throw_error(format!("Failed to execute git in '{}': {}", repoPath, error).as_str());
+            return;
+        }
+    };
+
+    let stderr = git_stderr(&output);
+    if !output.status.success() {
+        command_line::throw_error(format!("Failed to push '{}' to origin/{}: {}", repoName, branch_name, describe_git_error(&stderr)).as_str());
+        return;
+    }
+
+    if !has_changes && stderr.contains("Everything up-to-date") {
+        println!("'{}' is already up to date on origin/{}", repoName, branch_name);
+        return;
+    }
+
+    let commit_hash = run_git(repoPath, &["rev-parse", "--short", "HEAD"])
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_default();
+    println!("{} '{}' to origin/{} (commit {})", "Uploaded".green().bold(), repoName, branch_name, commit_hash.yellow());
+}
+
+// the git commands that talk to a remote and may need the token
+const NETWORK_COMMANDS: [&str; 5] = ["clone", "fetch", "push", "pull", "ls-remote"];
+
+/// Runs git inside `repoPath`. Git is never allowed to prompt for credentials, so a missing login fails instead of hanging.
+/// For commands that talk to a remote the configured token is handed to git through a temporary credential helper,
+/// every other command runs without it.
+pub fn run_git(repoPath: &str, args: &[&str]) -> io::Result<Output> {
+    let mut command = Command::new("git");
+    command.args(args).current_dir(repoPath).env("GIT_TERMINAL_PROMPT", "0");
+    if !args.first().is_some_and(|subcommand| NETWORK_COMMANDS.contains(subcommand)) {
+        return command.output();
+    }
+    // lives until git is done, the credential file goes with it
+    let git_env = credentials::git_env();
+    command.envs(git_env.vars()).output()
+}
+
+/// Like `run_git`, but a failing git command becomes an error with git's message.
+pub fn run_git_checked(repoPath: &str, args: &[&str]) -> Result<String, String> {
+    let output = run_git(repoPath, args).map_err(|error| format!("failed to execute git: {}", error))?;
+    if !output.status.success() {
+        return Err(git_stderr(&output));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+pub fn git_stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).trim().to_string()
+}
+
+pub fn current_branch(repoPath: &str) -> Option<String> {
+    let output = run_git(repoPath, &["rev-parse", "--abbrev-ref", "HEAD"]).ok()?;
+    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    // detached HEAD also prints "HEAD"
+    if !output.status.success() || branch.is_empty() || branch == "HEAD" {
+        return None;
+    }
+    Some(branch)
+}
+
+pub fn describe_git_error(stderr: &str) -> String {
+    let lower = stderr.to_lowercase();
+    if lower.contains("[rejected]") || lower.contains("stale info") {
+        "push was rejected because the remote has commits you don't have, run update first or use --force".to_string()
+    }
+    else if lower.contains("authentication failed") || lower.contains("could not read username")
+        || lower.contains("permission denied") || lower.contains("403") {
+        "authentication failed, check your credentials and access rights".to_string()
+    }
+    else if lower.contains("does not appear to be a git repository") || lower.contains("no such remote") {
+        "the repository has no 'origin' remote".to_string()
+    }
+    else {
+        stderr.to_string()
     }
 }
 
-pub fn migrate(projectPath: &String, repoName: &String, public: bool, api_key: &String){
-    println!("the user wants to migrate a project:");
-    println!("repo name: {}", repoName);
-    println!("current project path: {}", projectPath);
-    println!("Public: {}", public);
-    println!("API-Key: {}", api_key);
+#[derive(PartialEq, Clone, Copy)]
+pub enum UpdateStrategy {
+    FastForward,
+    Rebase,
+    Merge,
+}
+
+pub fn update(repoPath: &str, force: bool, branch_name: Option<String>, strategy: UpdateStrategy){
+    let repoName = Path::new(repoPath).file_name().and_then(|name| name.to_str()).unwrap_or(repoPath);
+
+    let current = current_branch(repoPath);
+    let branch_name = match branch_name.or_else(|| current.clone()) {
+        Some(branch) => branch,
+        None => {
+            command_line::throw_error(format!("Could not find out which branch '{}' is on (detached HEAD?), use --branch <name>", repoName).as_str());
+            return;
+        }
+    };
+
+    // fetch from the remote the branch is tracking, origin if it doesn't track anything
+    let remote = tracking_remote(repoPath, &branch_name).unwrap_or_else(|| "origin".to_string());
+    let output = match run_git(repoPath, &["fetch", &remote]) {
+        Ok(output) => output,
+        Err(error) => {
+            command_line::throw_error(format!("Failed to execute git in '{}': {}", repoPath, error).as_str());
+            return;
+        }
+    };
+    if !output.status.success() {
+        command_line::throw_error(format!("Failed to fetch '{}' from {}: {}", repoName, remote, describe_git_error(&git_stderr(&output))).as_str());
+        return;
+    }
+
+    // the upstream can be named differently than the branch, <remote>/<branch> is only the fallback
+    let remote_ref = upstream_ref(repoPath, &branch_name).unwrap_or_else(|| format!("refs/remotes/{}/{}", remote, branch_name));
+    let remote_branch = remote_ref.trim_start_matches("refs/remotes/").to_string();
+    let remote_exists = run_git(repoPath, &["rev-parse", "--verify", "--quiet", &remote_ref])
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+    if !remote_exists {
+        command_line::throw_error(format!("'{}' does not exist, the branch '{}' is not on {}", remote_branch, branch_name, remote).as_str());
+        return;
+    }
+
+    if force {
+        if !command_line::confirm(format!("This discards all local changes and commits in '{}' and resets it to {}. Continue?", repoName, remote_branch).as_str()) {
+            println!("Update of '{}' cancelled", repoName);
+            return;
+        }
+        if current.as_deref() != Some(branch_name.as_str()) && !git_succeeds(repoPath, &["checkout", "--force", &branch_name]) {
+            command_line::throw_error(format!("Failed to switch '{}' to branch '{}'", repoName, branch_name).as_str());
+            return;
+        }
+        match run_git(repoPath, &["reset", "--hard", &remote_branch]) {
+            Ok(output) if output.status.success() => println!("{} '{}' to {}", "Reset".green().bold(), repoName, remote_branch),
+            Ok(output) => command_line::throw_error(format!("Failed to reset '{}': {}", repoName, git_stderr(&output)).as_str()),
+            Err(error) => command_line::throw_error(format!("Failed to execute git in '{}': {}", repoPath, error).as_str()),
+        }
+        return;
+    }
+
+    // check everything up front so we never stop halfway through
+    if has_uncommitted_changes(repoPath) {
+        command_line::throw_error(format!("'{}' has uncommitted changes, upload them first or use --force to discard them", repoName).as_str());
+        return;
+    }
+
+    // compare the branch itself, it might not be checked out yet
+    let local_ref = format!("refs/heads/{}", branch_name);
+    let (ahead, behind) = if git_succeeds(repoPath, &["rev-parse", "--verify", "--quiet", &local_ref]) {
+        match ahead_behind(repoPath, &local_ref, &remote_branch) {
+            Some(counts) => counts,
+            None => {
+                command_line::throw_error(format!("Failed to compare '{}' with {}", repoName, remote_branch).as_str());
+                return;
+            }
+        }
+    }
+    else {
+        // checkout creates it from the remote branch
+        (0, 0)
+    };
+
+    if ahead > 0 && behind > 0 && strategy == UpdateStrategy::FastForward {
+        command_line::throw_error(format!("'{}' and {} have diverged ({} local, {} remote commit(s)), use --rebase or --merge", repoName, remote_branch, ahead, behind).as_str());
+        return;
+    }
+
+    if current.as_deref() != Some(branch_name.as_str()) && !git_succeeds(repoPath, &["checkout", &branch_name]) {
+        command_line::throw_error(format!("Failed to switch '{}' to branch '{}'", repoName, branch_name).as_str());
+        return;
+    }
+
+    if behind == 0 {
+        if ahead > 0 {
+            println!("'{}' is up to date with {} ({} local commit(s) not uploaded yet)", repoName, remote_branch, ahead);
+        }
+        else {
+            println!("'{}' is already up to date with {}", repoName, remote_branch);
+        }
+        return;
+    }
+
+    let (args, abort): (Vec<&str>, &[&str]) = match strategy {
+        UpdateStrategy::FastForward => (vec!["merge", "--ff-only", &remote_branch], &[]),
+        UpdateStrategy::Rebase => (vec!["rebase", &remote_branch], &["rebase", "--abort"]),
+        UpdateStrategy::Merge => (vec!["merge", "--no-edit", &remote_branch], &["merge", "--abort"]),
+    };
+
+    let output = match run_git(repoPath, &args) {
+        Ok(output) => output,
+        Err(error) => {
+            command_line::throw_error(format!("Failed to execute git in '{}': {}", repoPath, error).as_str());
+            return;
+        }
+    };
+    if !output.status.success() {
+        // leave the repository the way it was before
+        if !abort.is_empty() {
+            let _ = run_git(repoPath, abort);
+        }
+        command_line::throw_error(format!("Failed to update '{}' (conflicts?), nothing was changed: {}", repoName, git_stderr(&output)).as_str());
+        return;
+    }
+
+    println!("{} '{}': {} new commit(s) from {}", "Updated".green().bold(), repoName, behind, remote_branch);
+}
+
+/// Returns true if tracked files have changes that are not committed yet.
+/// If git can't tell, the changes are assumed to be there, callers use this to protect work.
+pub fn has_uncommitted_changes(repoPath: &str) -> bool {
+    run_git(repoPath, &["status", "--porcelain", "--untracked-files=no"])
+        .map(|output| !output.status.success() || !output.stdout.is_empty())
+        .unwrap_or(true)
+}
+
+/// Counts the commits only in `local` and only in `remote`.
+pub fn ahead_behind(repoPath: &str, local: &str, remote: &str) -> Option<(usize, usize)> {
+    let range = format!("{}...{}", local, remote);
+    let output = run_git(repoPath, &["rev-list", "--left-right", "--count", &range]).ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let counts = String::from_utf8_lossy(&output.stdout).to_string();
+    let mut counts = counts.split_whitespace().map(|count| count.parse::<usize>());
+    match (counts.next(), counts.next()) {
+        (Some(Ok(ahead)), Some(Ok(behind))) => Some((ahead, behind)),
+        _ => None,
+    }
+}
+
+pub fn tracking_remote(repoPath: &str, branch_name: &str) -> Option<String> {
+    let key = format!("branch.{}.remote", branch_name);
+    let output = run_git(repoPath, &["config", "--get", &key]).ok()?;
+    let remote = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || remote.is_empty() {
+        return None;
+    }
+    Some(remote)
+}
+
+/// The full name of the ref `branch_name` tracks, like refs/remotes/origin/main. None if it tracks nothing.
+pub fn upstream_ref(repoPath: &str, branch_name: &str) -> Option<String> {
+    let upstream = run_git_checked(repoPath, &["rev-parse", "--symbolic-full-name", &format!("{}@{{upstream}}", branch_name)]).ok()?;
+    if upstream.is_empty() {
+        return None;
+    }
+    Some(upstream)
+}
+
+pub fn git_succeeds(repoPath: &str, args: &[&str]) -> bool {
+    run_git(repoPath, args)
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+pub async fn download(repo_name: &str, username: &str, path: &str, options: &DownloadOptions, user_config: &Config) {
+    let clone_url = forge::clone_url(user_config, username, repo_name, options.ssh);
+    let job = bulk::CloneJob {
+        name: repo_name.to_string(),
+        target: format!("{}/{}", path, extract_repo_name(&clone_url)),
+        url: clone_url,
+    };
+    bulk::clone_repos(vec![job], 1, options.sync).await;
+}
+
+/// Turns a plain folder into a git repository and uploads it to a new repository on the forge.
+/// Everything that was created is removed again if a step fails.
+pub async fn migrate(projectPath: &str, repoName: &str, public: bool, user_config: &Config){
+    if Path::new(projectPath).join(".git").exists() {
+        command_line::throw_error(format!("'{}' is already a git repository", projectPath).as_str());
+        return;
+    }
+    match forge::repository_exists(user_config, &user_config.username, repoName).await {
+        Ok(false) => {}
+        Ok(true) => {
+            command_line::throw_error(format!("Repository '{}' already exists on your {} account", repoName, forge::name(user_config)).as_str());
+            return;
+        }
+        Err(error) => {
+            command_line::throw_error(format!("Could not check if '{}' already exists: {}", repoName, error).as_str());
+            return;
+        }
+    }
+
+    let gitignore_path = Path::new(projectPath).join(".gitignore");
+    let created_gitignore = !gitignore_path.exists();
+
+    if let Err(error) = run_git_checked(projectPath, &["init", "--initial-branch=main"]) {
+        command_line::throw_error(format!("Failed to run git init in '{}': {}", projectPath, error).as_str());
+        return;
+    }
+
+    if created_gitignore {
+        let project_types = detect_project_types(projectPath);
+        if let Err(error) = fs::write(&gitignore_path, generate_gitignore(&project_types)) {
+            command_line::throw_error(format!("Failed to write .gitignore: {}", error).as_str());
+            rollback_migration(projectPath, created_gitignore);
+            return;
+        }
+        if !project_types.is_empty() {
+            println!("Generated .gitignore for {}", project_types.join(", "));
+        }
+    }
+
+    let commit = run_git_checked(projectPath, &["add", "--all"])
+        .and_then(|_| run_git_checked(projectPath, &["commit", "-m", "Initial commit"]));
+    if let Err(error) = commit {
+        command_line::throw_error(format!("Failed to create the first commit: {}", error).as_str());
+        rollback_migration(projectPath, created_gitignore);
+        return;
+    }
+
+    let remote = match forge::create_repository(user_config, repoName, public).await {
+        Ok(remote) => remote,
+        Err(error) => {
+            command_line::throw_error(format!("Failed to create repository '{}': {}", repoName, error).as_str());
+            rollback_migration(projectPath, created_gitignore);
+            return;
+        }
+    };
+
+    let push = run_git_checked(projectPath, &["remote", "add", "origin", remote.remote_url(user_config)])
+        .and_then(|_| run_git_checked(projectPath, &["push", "--set-upstream", "origin", "HEAD"]));
+    if let Err(error) = push {
+        command_line::throw_error(format!("Failed to push '{}': {}", repoName, describe_git_error(&error)).as_str());
+        rollback_migration(projectPath, created_gitignore);
+        // the remote is still empty, so removing it loses nothing
+        if let Err(error) = forge::delete_repository(user_config, &remote.owner.login, &remote.name).await {
+            command_line::throw_error(format!("Could not remove '{}' from {} again, delete it by hand: {}", remote.full_name, forge::name(user_config), error).as_str());
+        }
+        return;
+    }
+
+    println!("{} '{}' to {} ({})", "Migrated".green().bold(), projectPath, remote.html_url, if remote.private { "private" } else { "public" });
+}
+
+fn rollback_migration(projectPath: &str, created_gitignore: bool) {
+    let _ = fs::remove_dir_all(Path::new(projectPath).join(".git"));
+    if created_gitignore {
+        let _ = fs::remove_file(Path::new(projectPath).join(".gitignore"));
+    }
+    println!("Rolled back the changes in '{}'", projectPath);
+}
+
+/// Guesses the kind of project from the files in its top level folder.
+pub fn detect_project_types(projectPath: &str) -> Vec<&'static str> {
+    let has = |file: &str| Path::new(projectPath).join(file).exists();
+    let mut project_types = Vec::new();
+
+    if has("Cargo.toml") {
+        project_types.push("Rust");
+    }
+    if has("package.json") {
+        project_types.push("Node");
+    }
+    if has("pyproject.toml") || has("requirements.txt") || has("setup.py") {
+        project_types.push("Python");
+    }
+    if has("pom.xml") || has("build.gradle") || has("build.gradle.kts") {
+        project_types.push("Java");
+    }
+    if has("go.mod") {
+        project_types.push("Go");
+    }
+    if has("CMakeLists.txt") || has("Makefile") {
+        project_types.push("C/C++");
+    }
+    project_types
+}
+
+pub fn generate_gitignore(project_types: &[&str]) -> String {
+    let mut gitignore = String::from("# generated by Git-Manager\n.DS_Store\nThumbs.db\n.idea/\n.vscode/\n");
+
+    for project_type in project_types {
+        let entries = match *project_type {
+            "Rust" => "/target\n",
+            "Node" => "node_modules/\ndist/\n.env\n",
+            "Python" => "__pycache__/\n*.py[cod]\n.venv/\nvenv/\n*.egg-info/\n.env\n",
+            "Java" => "target/\nbuild/\n.gradle/\n*.class\n",
+            "Go" => "/bin/\n*.exe\n",
+            "C/C++" => "build/\n*.o\n*.a\n*.so\n",
+            _ => "",
+        };
+        gitignore.push_str(&format!("\n# {}\n{}", project_type, entries));
+    }
+    gitignore
 }
 
 pub fn find_git_repos(path: &Path) -> Vec<PathBuf> {
@@ -62,20 +773,18 @@ pub fn find_git_repos(path: &Path) -> Vec<PathBuf> {
 
     // Rekursiv alle Einträge im Pfad durchgehen
     if let Ok(entries) = fs::read_dir(path) {
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let entry_path = entry.path();
-                if entry_path.is_dir() {
-                    // Wenn das Verzeichnis noch nicht als Git-Repo gefunden wurde
-                    if !git_repos.iter().any(|repo: &PathBuf | repo.starts_with(&entry_path)) {
-                        // Nach .git-Dateien suchen
-                        let git_dir = entry_path.join(".git");
-                        if git_dir.exists() {
-                            git_repos.push(entry_path);
-                        } else {
-                            // Rekursiv in Unterverzeichnisse suchen
-                            git_repos.extend(find_git_repos(&entry_path));
-                        }
+        for entry in entries.flatten() {
+            let entry_path = entry.path();
+            if entry_path.is_dir() {
+                // Wenn das Verzeichnis noch nicht als Git-Repo gefunden wurde
+                if !git_repos.iter().any(|repo: &PathBuf | repo.starts_with(&entry_path)) {
+                    // Nach .git-Dateien suchen
+                    let git_dir = entry_path.join(".git");
+                    if git_dir.exists() {
+                        git_repos.push(entry_path);
+                    } else {
+                        // Rekursiv in Unterverzeichnisse suchen
+                        git_repos.extend(find_git_repos(&entry_path));
                     }
                 }
             }
@@ -85,50 +794,104 @@ pub fn find_git_repos(path: &Path) -> Vec<PathBuf> {
     git_repos
 }
 
-pub async fn clone_all_repos(username: &str, token: Option<&str>, target_path: &str) -> Result<(), Box<dyn std::error::Error>> {
-    // Erstelle den Client
-    let client = reqwest::Client::new();
+/// Clones every repository of `username`. For the configured user private ones are included.
+pub async fn clone_all_repos(username: &str, target_path: &str, options: &DownloadOptions, user_config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    let owner = if username.eq_ignore_ascii_case(&user_config.username) { forge::RepoOwner::Own } else { forge::RepoOwner::User(username) };
+    clone_listed_repos(forge::list_repositories(user_config, owner).await, target_path, username, options).await
+}
 
-    // Erstelle die URL
-    let url = format!("https://api.github.com/users/{}/repos?per_page=100", username);
+/// Clones every repository of an organization (a group on GitLab) the configured token can see, private ones included.
+pub async fn clone_all_org_repos(org: &str, target_path: &str, options: &DownloadOptions, user_config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    clone_listed_repos(forge::list_repositories(user_config, forge::RepoOwner::Group(org)).await, target_path, org, options).await
+}
 
-    // Füge optional den Authorization Header hinzu
-    let mut request = client.get(&url).header("User-Agent", "rust-github-client");
+async fn clone_listed_repos(repos: Result<Vec<CloneData>, Box<dyn std::error::Error>>, target_path: &str, owner: &str, options: &DownloadOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let repos = match repos {
+        Ok(repos) => repos,
+        Err(error) => {
+            eprintln!("{}", error);
+            return Ok(());
+        }
+    };
+    println!("Found {} repositories of {}", repos.len(), owner);
 
-    if let Some(token) = token {
-        let auth_value = format!("token {}", token);
-        request = request.header(header::AUTHORIZATION, auth_value);
+    let repos = repos.into_iter().filter(|repo| options.matches(repo)).collect::<Vec<CloneData>>();
+    if options.has_filters() {
+        println!("{} of them match the filters", repos.len());
     }
 
-    // Sende die Anfrage
-    let response = request.send().await?;
+    // Klone die Repositories parallel
+    let jobs = repos.into_iter()
+        .map(|repo| bulk::CloneJob {
+            target: format!("{}/{}", target_path, extract_repo_name(&repo.clone_url)),
+            name: repo.name,
+            url: if options.ssh { repo.ssh_url } else { repo.clone_url },
+        })
+        .collect();
+    bulk::clone_repos(jobs, options.jobs, options.sync).await;
 
-    // Überprüfe den Status der Antwort
-    if !response.status().is_success() {
-        eprintln!("Failed to fetch repos: {}", response.status());
-        return Ok(());
-    }
+    Ok(())
+}
 
-    // Parste die JSON Antwort
-    let repos: Vec<CloneData> = response.json().await?;
+/// Prints every repository of `username` on the forge and marks the ones that are cloned already.
+pub async fn list_remote_repos(username: &str, repo_list: &[Repository], user_config: &Config) {
+    let owner = if username.eq_ignore_ascii_case(&user_config.username) { forge::RepoOwner::Own } else { forge::RepoOwner::User(username) };
+    let repos = match forge::list_repositories(user_config, owner).await {
+        Ok(repos) => repos,
+        Err(error) => {
+            command_line::throw_error(error.to_string().as_str());
+            return;
+        }
+    };
+    if repos.is_empty() {
+        println!("{} has no repositories you can see", username);
+        return;
+    }
 
-    // Klone jedes Repository
-    for repo in repos {
-        let output = Command::new("git")
-            .arg("clone")
-            .arg(&repo.clone_url)
-            .arg(format!("{}/{}", target_path, extract_repo_name(&repo.clone_url)))
-            .output()
-            .expect("Failed to execute git command");
+    let max_name_length = repos.iter().map(|repo| repo.name.len()).max().unwrap_or(0);
+    let mut cloned = 0;
+    for repo in &repos {
+        let is_cloned = repo_list.iter().any(|local| local.Name.eq_ignore_ascii_case(&repo.name));
+        if is_cloned {
+            cloned += 1;
+        }
 
-        if !output.status.success() {
-            eprintln!("Failed to clone repo: {}", repo.clone_url);
-        } else {
-            println!("Successfully cloned: {}", repo.clone_url);
+        let visibility = if repo.private { "private".red() } else { "public ".green() };
+        let mut flags = Vec::new();
+        if repo.fork {
+            flags.push("fork");
+        }
+        if repo.archived {
+            flags.push("archived");
         }
+        let pushed = repo.pushed_at.as_deref()
+            .and_then(|date| date.split('T').next())
+            .unwrap_or("never");
+
+        println!(
+            "{}{}  {}  {:<14}  {:<8}  {}  {}{}",
+            repo.name.blue().bold(),
+            " ".repeat(max_name_length - repo.name.len()),
+            visibility,
+            repo.language.as_deref().unwrap_or("-"),
+            format!("★ {}", repo.stargazers_count),
+            format!("pushed {}", pushed).italic(),
+            flags.join(", ").yellow(),
+            if is_cloned { " (cloned)".green().bold().to_string() } else { String::new() },
+        );
     }
+    println!("{} repositories, {} cloned locally", repos.len(), cloned);
+}
 
-    Ok(())
+/// --ssh / --https win over the clone_protocol of the config.
+pub fn use_ssh(args: &[&str], user_config: &Config) -> bool {
+    if args.contains(&"--ssh") {
+        return true;
+    }
+    if args.contains(&"--https") {
+        return false;
+    }
+    user_config.clone_protocol.eq_ignore_ascii_case("ssh")
 }
 
 pub fn extract_repo_name(clone_url: &str) -> &str {
@@ -151,7 +914,7 @@ pub fn get_all_repositories(user_config: &Config) -> Vec<Repository>{
         let repo = Repository {
             Name: name.clone(),
             Path: cleanPath.to_string(),
-            clone_url: "https://github.com/".to_string() + &user_config.username+ "/" + &name + ".git",
+            clone_url: forge::clone_url(user_config, &user_config.username, &name, use_ssh(&[], user_config)),
         };
         repo_list.push(repo);
         
@@ -160,8 +923,8 @@ pub fn get_all_repositories(user_config: &Config) -> Vec<Repository>{
     repo_list
 }
 
-pub fn print_repo_list(repo_paths: &Vec<String>) {
-    for path in repo_paths.clone() {
+pub fn print_repo_list(repo_paths: &[String]) {
+    for path in repo_paths {
         let cleanPathSplit = path.split_inclusive("/").collect::<Vec<&str>>();
         
         println!("{}{}", cleanPathSplit[0..cleanPathSplit.len() - 2].join("").italic(), cleanPathSplit.last().unwrap().blue().italic().bold());
@@ -212,8 +975,12 @@ pub fn find_file_in_path(path: &str, name: &str) -> Result<String, SearchError>
 
 
 
-pub fn deleteDir(path: &String){
-    println!("The user wants to delete a folder at: {}", &path)
+/// Moves the folder into the trash, it's only deleted for good by 'trash empty'.
+pub fn deleteDir(path: &str){
+    match trash::move_to_trash(path) {
+        Ok(id) => println!("{} '{}' to the trash, get it back with 'trash restore {}'", "Moved".green().bold(), path, id),
+        Err(error) => command_line::throw_error(format!("Failed to delete '{}': {}", path, error).as_str()),
+    }
 }
 
 
@@ -233,19 +1000,30 @@ pub fn update_repos(repo_list: &mut Vec<Repository>, repo_names_list: &mut Vec<S
 
 
 
-pub fn get_commit_msg(args: &Vec<&str>, start: usize) -> String{
+pub fn get_commit_msg(args: &[&str], start: usize) -> String{
     let mut commit_msg = String::new();
-    let mut force = false;
+    let mut skip_next = false;
 
     for arg in &args[start..] {
-        if arg != &"--force"{
-            commit_msg.push_str(arg);
-            commit_msg.push_str(" ");
+        if skip_next {
+            skip_next = false;
+            continue;
+        }
+        // get_force and get_branch_name ignore the case of the flags, so this has to as well
+        if arg.eq_ignore_ascii_case("--force") {
+            continue;
         }
+        // the value after --branch is the branch name, not part of the message
+        if arg.eq_ignore_ascii_case("--branch") || *arg == "-b" {
+            skip_next = true;
+            continue;
+        }
+        commit_msg.push_str(arg);
+        commit_msg.push(' ');
     }
     commit_msg = commit_msg.trim().to_string();
 
-    if commit_msg.len() == 0 {
+    if commit_msg.is_empty() {
         commit_msg = "commited by Git-Manager".to_string();
     }
 
@@ -258,5 +1036,279 @@ pub fn get_force(args: &Vec<&str>) -> bool {
             return true;
         }
     }
-    return false;
-}
\ No newline at end of file
+    false
+}
+
+/// Returns the value of `--branch <name>` (or `-b <name>`) if it was given.
+pub fn get_branch_name(args: &[&str]) -> Option<String> {
+    let position = args.iter().position(|arg| arg.eq_ignore_ascii_case("--branch") || *arg == "-b")?;
+    let name = args.get(position + 1)?.trim();
+    if name.is_empty() {
+        return None;
+    }
+    Some(name.to_string())
+}
+
+/// Reads `--rebase` / `--merge`, only fast-forwards without either. Both at once is an error.
+pub fn get_update_strategy(args: &[&str]) -> Result<UpdateStrategy, String> {
+    match (args.contains(&"--rebase"), args.contains(&"--merge")) {
+        (true, true) => Err("Use either --rebase or --merge, not both".to_string()),
+        (true, false) => Ok(UpdateStrategy::Rebase),
+        (false, true) => Ok(UpdateStrategy::Merge),
+        (false, false) => Ok(UpdateStrategy::FastForward),
+    }
+}
+
+/// Reads `--local` / `--remote` for branch deletion, no flag means both.
+pub fn get_branch_targets(args: &[&str]) -> (bool, bool) {
+    let local = args.contains(&"--local");
+    let remote = args.contains(&"--remote");
+    if !local && !remote {
+        return (true, true);
+    }
+    (local, remote)
+}
+
+/// Returns the path after the keyword 'to' (download ... to <path>), or `default` if there is none.
+/// Only words from `start` on are searched, so a repository or user called 'to' isn't taken for the keyword.
+pub fn get_target_path(args: &[&str], rawArgs: &[&str], start: usize, default: &str) -> String {
+    match args.iter().skip(start).position(|arg| *arg == "to").map(|position| position + start) {
+        Some(position) if rawArgs.get(position + 1).is_some_and(|path| path.trim() != "") => rawArgs[position + 1].to_string(),
+        _ => default.to_string(),
+    }
+}
+
+pub struct DownloadOptions {
+    pub jobs: usize,
+    // fetch existing clones instead of failing on them
+    pub sync: bool,
+    pub no_forks: bool,
+    pub no_archived: bool,
+    pub language: Option<String>,
+    pub include: Vec<String>, // name globs, one has to match
+    pub exclude: Vec<String>, // name globs, none may match
+    pub topics: Vec<String>,  // all have to be set
+    pub ssh: bool,
+}
+
+impl DownloadOptions {
+    pub fn has_filters(&self) -> bool {
+        self.no_forks || self.no_archived || self.language.is_some()
+            || !self.include.is_empty() || !self.exclude.is_empty() || !self.topics.is_empty()
+    }
+
+    /// Checks the metadata of a repository against the filters.
+    fn matches(&self, repo: &CloneData) -> bool {
+        let name = repo.name.to_lowercase();
+
+        if self.no_forks && repo.fork {
+            return false;
+        }
+        if self.no_archived && repo.archived {
+            return false;
+        }
+        if let Some(language) = &self.language {
+            if !repo.language.as_deref().is_some_and(|repo_language| repo_language.eq_ignore_ascii_case(language)) {
+                return false;
+            }
+        }
+        if !self.include.is_empty() && !self.include.iter().any(|pattern| glob_match(pattern, &name)) {
+            return false;
+        }
+        if self.exclude.iter().any(|pattern| glob_match(pattern, &name)) {
+            return false;
+        }
+        self.topics.iter().all(|topic| repo.topics.iter().any(|repo_topic| repo_topic.eq_ignore_ascii_case(topic)))
+    }
+}
+
+/// Reads the flags of 'download all', falling back to the config.
+pub fn get_download_options(args: &[&str], rawArgs: &[&str], user_config: &Config) -> DownloadOptions {
+    let jobs = get_flag_values(args, "--jobs")
+        .first()
+        .and_then(|jobs| jobs.parse::<usize>().ok())
+        .filter(|jobs| *jobs > 0)
+        .unwrap_or(user_config.clone_jobs);
+
+    DownloadOptions {
+        jobs,
+        sync: args.contains(&"--sync"),
+        no_forks: args.contains(&"--no-forks"),
+        no_archived: args.contains(&"--no-archived"),
+        language: get_flag_values(rawArgs, "--language").pop(),
+        include: get_flag_values(args, "--match"),
+        exclude: get_flag_values(args, "--exclude"),
+        topics: get_flag_values(args, "--topic"),
+        ssh: use_ssh(args, user_config),
+    }
+}
+
+/// Collects the value after every occurrence of `flag`, without surrounding quotes.
+/// A quoted value can span several words, like --label "good first issue".
+pub fn get_flag_values(args: &[&str], flag: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut position = 0;
+    while position < args.len() {
+        position += 1;
+        if !args[position - 1].eq_ignore_ascii_case(flag) {
+            continue;
+        }
+        let first = match args.get(position) {
+            Some(first) => first.trim(),
+            None => break,
+        };
+        let mut words = vec![first];
+        position += 1;
+
+        // keep taking words until the one that closes the quote
+        if let Some(quote) = first.chars().next().filter(|character| *character == '"' || *character == '\'') {
+            let mut closed = first.len() > 1 && first.ends_with(quote);
+            while !closed && position < args.len() {
+                let word = args[position].trim();
+                words.push(word);
+                position += 1;
+                closed = word.ends_with(quote);
+            }
+        }
+
+        let value = words.join(" ").trim().trim_matches(|character| character == '\'' || character == '"').trim().to_string();
+        if !value.is_empty() {
+            values.push(value);
+        }
+    }
+    values
+}
+
+/// Matches `*` (any number of characters) and `?` (one character) globs.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<char>>();
+    let text = text.chars().collect::<Vec<char>>();
+    let (mut p, mut t) = (0, 0);
+    // where the last * was and which text position it's matching up to
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        }
+        else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        }
+        else if let Some((star_p, star_t)) = star {
+            // let the * swallow one more character
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        }
+        else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|character| *character == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_match_handles_stars_at_the_ends() {
+        assert!(glob_match("rust-*", "rust-cli"));
+        assert!(glob_match("*-cli", "rust-cli"));
+        assert!(glob_match("*cli*", "my-cli-tool"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("rust-*", "rust-"));
+        assert!(!glob_match("rust-*", "rus"));
+        assert!(!glob_match("*-cli", "rust-client"));
+    }
+
+    #[test]
+    fn glob_match_backtracks_and_matches_single_characters() {
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(glob_match("v?", "v2"));
+        assert!(!glob_match("v?", "v"));
+        assert!(!glob_match("a*b", "aXbY"));
+        assert!(!glob_match("", "a"));
+    }
+
+    #[test]
+    fn parse_full_name_reads_https_and_ssh_remotes() {
+        let expected = Some(("octocat".to_string(), "hello-world".to_string()));
+        assert_eq!(parse_full_name("https://github.com/octocat/hello-world.git"), expected);
+        assert_eq!(parse_full_name("https://github.com/octocat/hello-world"), expected);
+        assert_eq!(parse_full_name("https://github.com/octocat/hello-world/"), expected);
+        assert_eq!(parse_full_name("git@github.com:octocat/hello-world.git"), expected);
+        assert_eq!(parse_full_name("ssh://git@github.com/octocat/hello-world.git"), expected);
+    }
+
+    #[test]
+    fn parse_full_name_keeps_nested_groups_in_the_owner() {
+        assert_eq!(parse_full_name("https://gitlab.com/group/subgroup/project.git"), Some(("group/subgroup".to_string(), "project".to_string())));
+        assert_eq!(parse_full_name("git@gitlab.com:group/subgroup/project.git"), Some(("group/subgroup".to_string(), "project".to_string())));
+    }
+
+    #[test]
+    fn parse_full_name_rejects_urls_without_owner() {
+        assert_eq!(parse_full_name("https://github.com/hello-world"), None);
+        assert_eq!(parse_full_name("/home/me/hello-world"), None);
+        assert_eq!(parse_full_name(""), None);
+    }
+
+    #[test]
+    fn get_commit_msg_leaves_out_flags_and_the_branch_name() {
+        let args = ["upload", "fix", "the", "typo", "--branch", "dev", "--force", " ", " "];
+        assert_eq!(get_commit_msg(&args, 1), "fix the typo");
+        let args = ["upload", "my-repo", "-b", "dev", "update", "readme", " "];
+        assert_eq!(get_commit_msg(&args, 2), "update readme");
+        let args = ["upload", "fix", "--Branch", "dev", "--FORCE", " "];
+        assert_eq!(get_commit_msg(&args, 1), "fix");
+    }
+
+    #[test]
+    fn get_commit_msg_has_a_default() {
+        assert_eq!(get_commit_msg(&["upload", " ", " "], 1), "commited by Git-Manager");
+        assert_eq!(get_commit_msg(&["upload", "--branch", "dev", " "], 1), "commited by Git-Manager");
+    }
+
+    #[test]
+    fn get_flag_values_joins_quoted_words() {
+        let args = ["issue", "list", "--label", "\"good", "first", "issue\"", "--label", "bug", " "];
+        assert_eq!(get_flag_values(&args, "--label"), vec!["good first issue".to_string(), "bug".to_string()]);
+        let args = ["pr", "create", "--BASE", "'dev'", " "];
+        assert_eq!(get_flag_values(&args, "--base"), vec!["dev".to_string()]);
+        // the padding main adds is not a value
+        assert!(get_flag_values(&["issue", "list", "--label", " "], "--label").is_empty());
+        assert!(get_flag_values(&["issue", "list", "--label"], "--label").is_empty());
+    }
+
+    #[test]
+    fn get_target_path_looks_after_the_owner() {
+        let args = ["download", "repo", "from", "alice", "to", "/tmp/code", " "];
+        assert_eq!(get_target_path(&args, &args, 4, "default"), "/tmp/code");
+        let args = ["download", "to", "from", "alice", " ", " "];
+        assert_eq!(get_target_path(&args, &args, 4, "default"), "default");
+        let args = ["download", "all", "from", "to", " ", " "];
+        assert_eq!(get_target_path(&args, &args, 4, "default"), "default");
+        let args = ["download", "all", "from", "to", "to", "/tmp/code"];
+        assert_eq!(get_target_path(&args, &args, 4, "default"), "/tmp/code");
+    }
+
+    #[test]
+    fn get_update_strategy_refuses_rebase_and_merge_together() {
+        assert!(get_update_strategy(&["update", "repo", "--rebase"]) == Ok(UpdateStrategy::Rebase));
+        assert!(get_update_strategy(&["update", "repo", " "]) == Ok(UpdateStrategy::FastForward));
+        assert!(get_update_strategy(&["update", "repo", "--merge", "--rebase"]).is_err());
+    }
+
+    #[test]
+    fn get_branch_name_reads_the_value_after_the_flag() {
+        assert_eq!(get_branch_name(&["upload", "--branch", "dev", " "]), Some("dev".to_string()));
+        assert_eq!(get_branch_name(&["upload", "-b", "feature/x"]), Some("feature/x".to_string()));
+        assert_eq!(get_branch_name(&["upload", "fix", " "]), None);
+        // the padding main adds is not a branch name
+        assert_eq!(get_branch_name(&["upload", "--branch", " "]), None);
+        assert_eq!(get_branch_name(&["upload", "--branch"]), None);
+    }
+}
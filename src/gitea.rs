@@ -0,0 +1,444 @@
+use crate::*;
+use forge::{Checks, Comment, Forge, Head, Issue, IssueFilter, MergeMethod, PullRequest, Release, RemoteRepository, RepoOwner, RepoUpdate};
+use github::{CombinedStatus, CommentResponse, IssueResponse, PullResponse, ReleaseResponse};
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+
+/// A repository in the Gitea list endpoints, only the fields that differ from GitHub are renamed.
+#[derive(Deserialize)]
+struct Repo {
+    clone_url: String,
+    ssh_url: String,
+    name: String,
+    #[serde(default)]
+    private: bool,
+    #[serde(default)]
+    fork: bool,
+    #[serde(default)]
+    archived: bool,
+    // empty instead of missing when unknown
+    #[serde(default)]
+    language: String,
+    #[serde(default)]
+    stars_count: u32,
+    updated_at: Option<String>,
+    #[serde(default)]
+    topics: Option<Vec<String>>,
+}
+
+impl Repo {
+    fn into_clone_data(self) -> CloneData {
+        CloneData {
+            clone_url: self.clone_url,
+            ssh_url: self.ssh_url,
+            name: self.name,
+            private: self.private,
+            fork: self.fork,
+            archived: self.archived,
+            language: Some(self.language).filter(|language| !language.is_empty()),
+            stargazers_count: self.stars_count,
+            pushed_at: self.updated_at,
+            topics: self.topics.unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ApiErrorBody {
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    errors: Vec<String>,
+}
+
+/// Gitea and Forgejo API v1, api_url is https://<host>/api/v1 and the api key an access token.
+pub struct Gitea<'a> {
+    user_config: &'a Config,
+}
+
+impl<'a> Gitea<'a> {
+    pub fn new(user_config: &'a Config) -> Self {
+        Gitea { user_config }
+    }
+
+    fn request(&self, method: Method, endpoint: &str) -> RequestBuilder {
+        let url = format!("{}{}", self.user_config.api_url.trim_end_matches('/'), endpoint);
+        self.authorize(api::request(method, &url))
+    }
+
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        let api_key = &self.user_config.api_key;
+        if api_key.trim().is_empty() {
+            return request;
+        }
+        request.header(header::AUTHORIZATION, format!("token {}", api_key))
+    }
+
+    /// Requests `endpoint` and every following page (`Link: rel="next"`) and collects all items.
+    async fn get_all_pages<T: DeserializeOwned>(&self, endpoint: &str) -> Result<Vec<T>, Box<dyn std::error::Error>> {
+        let mut items = Vec::new();
+        let mut next_url = Some(format!("{}{}", self.user_config.api_url.trim_end_matches('/'), endpoint));
+
+        while let Some(url) = next_url {
+            let response = api::send(self.authorize(api::request(Method::GET, &url))).await?;
+            if !response.status().is_success() {
+                return Err(api_error(self.user_config, response).await);
+            }
+            next_url = api::next_page(&response);
+
+            let page: Vec<T> = response.json().await?;
+            items.extend(page);
+        }
+        Ok(items)
+    }
+
+    // Gitea Actions report through commit statuses as well
+    async fn checks(&self, owner: &str, name: &str, sha: &str) -> Checks {
+        let response = match api::send(self.request(Method::GET, &format!("/repos/{}/{}/commits/{}/status", owner, name, sha))).await {
+            Ok(response) if response.status().is_success() => response,
+            _ => return Checks::Missing,
+        };
+        response.json::<CombinedStatus>().await
+            .map(|status| status.checks())
+            .unwrap_or(Checks::Missing)
+    }
+}
+
+/// Turns an unsuccessful response into a readable error with the message the server sent.
+pub async fn api_error(user_config: &Config, response: Response) -> Box<dyn std::error::Error> {
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+
+    let mut message = format!("{} answered {}", forge::name(user_config), status);
+    match serde_json::from_str::<ApiErrorBody>(&text) {
+        Ok(body) => {
+            if !body.message.is_empty() {
+                message.push_str(&format!(": {}", body.message));
+            }
+            for detail in body.errors {
+                message.push_str(&format!(" ({})", detail));
+            }
+        }
+        Err(_) if !text.trim().is_empty() => message.push_str(&format!(": {}", text.trim())),
+        Err(_) => {}
+    }
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        message.push_str(" - check that your token has the 'repository' and 'user' permissions");
+    }
+    message.into()
+}
+
+impl Forge for Gitea<'_> {
+    fn user_config(&self) -> &Config {
+        self.user_config
+    }
+
+    // the single repository has the same shape as on GitHub
+    async fn get_repository(&self, owner: &str, name: &str) -> Result<Option<RemoteRepository>, Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::GET, &format!("/repos/{}/{}", owner, name))).await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(api_error(self.user_config, response).await);
+        }
+        Ok(Some(response.json().await?))
+    }
+
+    async fn create_repository(&self, name: &str, public: bool) -> Result<RemoteRepository, Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "name": name,
+            "private": !public,
+        });
+        let response = api::send(self.request(Method::POST, "/user/repos").json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(self.user_config, response).await);
+        }
+        Ok(response.json().await?)
+    }
+
+    async fn delete_repository(&self, owner: &str, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::DELETE, &format!("/repos/{}/{}", owner, name))).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(self.user_config, response).await);
+        }
+        Ok(())
+    }
+
+    async fn fork_repository(&self, owner: &str, name: &str) -> Result<RemoteRepository, Box<dyn std::error::Error>> {
+        let body = serde_json::json!({});
+        let response = api::send(self.request(Method::POST, &format!("/repos/{}/{}/forks", owner, name)).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(self.user_config, response).await);
+        }
+        Ok(response.json().await?)
+    }
+
+    // the same fields as on GitHub
+    async fn update_repository(&self, owner: &str, name: &str, update: &RepoUpdate) -> Result<RemoteRepository, Box<dyn std::error::Error>> {
+        let mut body = serde_json::Map::new();
+        if let Some(new_name) = &update.name {
+            body.insert("name".to_string(), serde_json::json!(new_name));
+        }
+        if let Some(private) = update.private {
+            body.insert("private".to_string(), serde_json::json!(private));
+        }
+        if let Some(description) = &update.description {
+            body.insert("description".to_string(), serde_json::json!(description));
+        }
+        if let Some(archived) = update.archived {
+            body.insert("archived".to_string(), serde_json::json!(archived));
+        }
+        let response = api::send(self.request(Method::PATCH, &format!("/repos/{}/{}", owner, name)).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(self.user_config, response).await);
+        }
+        Ok(response.json().await?)
+    }
+
+    async fn set_topics(&self, owner: &str, name: &str, topics: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "topics": topics,
+        });
+        let response = api::send(self.request(Method::PUT, &format!("/repos/{}/{}/topics", owner, name)).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(self.user_config, response).await);
+        }
+        Ok(())
+    }
+
+    async fn list_repositories(&self, owner: RepoOwner<'_>) -> Result<Vec<CloneData>, Box<dyn std::error::Error>> {
+        let endpoint = match owner {
+            // with the token of the owner this includes private repositories
+            RepoOwner::Own => format!("/users/{}/repos?limit=50", self.user_config.username),
+            RepoOwner::User(user) => format!("/users/{}/repos?limit=50", user),
+            RepoOwner::Group(org) => format!("/orgs/{}/repos?limit=50", org),
+        };
+        let repos: Vec<Repo> = self.get_all_pages(&endpoint).await?;
+        Ok(repos.into_iter().map(Repo::into_clone_data).collect())
+    }
+
+    async fn branch_exists(&self, owner: &str, name: &str, branch: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::GET, &format!("/repos/{}/{}/branches/{}", owner, name, api::encode(branch)))).await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        if !response.status().is_success() {
+            return Err(api_error(self.user_config, response).await);
+        }
+        Ok(true)
+    }
+
+    async fn delete_branch(&self, owner: &str, name: &str, branch: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::DELETE, &format!("/repos/{}/{}/branches/{}", owner, name, api::encode(branch)))).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(self.user_config, response).await);
+        }
+        Ok(())
+    }
+
+    /// Gitea doesn't limit API requests by default, this shows the headers if a proxy in front of it does.
+    async fn print_rate_limit(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::GET, "/version")).await?;
+        if !response.status().is_success() {
+            return Err(api_error(self.user_config, response).await);
+        }
+
+        // the headers of this response, not whatever was requested last
+        match api::read_rate_limit(response.headers()) {
+            Some(rate_limit) => {
+                let remaining = format!("{}/{}", rate_limit.remaining, rate_limit.limit);
+                let remaining = if rate_limit.remaining == 0 { remaining.red().bold() } else { remaining.green() };
+                println!("{:<8} {} requests left, resets {}", "api".blue().bold(), remaining, api::format_reset(rate_limit.reset));
+            }
+            None => println!("This {} instance doesn't limit API requests", forge::name(self.user_config)),
+        }
+        Ok(())
+    }
+
+    // pull requests have the same shape as on GitHub
+    async fn create_pull_request(&self, owner: &str, name: &str, head: &Head<'_>, base: &str, title: &str, body: &str) -> Result<PullRequest, Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "title": title,
+            "body": body,
+            "head": head.qualified(owner, name),
+            "base": base,
+        });
+        let response = api::send(self.request(Method::POST, &format!("/repos/{}/{}/pulls", owner, name)).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(self.user_config, response).await);
+        }
+        let pull: PullResponse = response.json().await?;
+        Ok(pull.into_pull_request(Checks::Missing))
+    }
+
+    async fn get_pull_request(&self, owner: &str, name: &str, number: u64) -> Result<Option<PullRequest>, Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::GET, &format!("/repos/{}/{}/pulls/{}", owner, name, number))).await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(api_error(self.user_config, response).await);
+        }
+        let pull: PullResponse = response.json().await?;
+        let pull = pull.into_pull_request(Checks::Missing);
+        let checks = self.checks(owner, name, &pull.head_sha).await;
+        Ok(Some(PullRequest { checks, ..pull }))
+    }
+
+    async fn list_pull_requests(&self, owner: &str, name: &str) -> Result<Vec<PullRequest>, Box<dyn std::error::Error>> {
+        let pulls: Vec<PullResponse> = self.get_all_pages(&format!("/repos/{}/{}/pulls?state=open&limit=50", owner, name)).await?;
+
+        let mut pull_requests = Vec::new();
+        for pull in pulls {
+            let pull = pull.into_pull_request(Checks::Missing);
+            let checks = self.checks(owner, name, &pull.head_sha).await;
+            pull_requests.push(PullRequest { checks, ..pull });
+        }
+        Ok(pull_requests)
+    }
+
+    async fn merge_pull_request(&self, owner: &str, name: &str, number: u64, method: MergeMethod) -> Result<(), Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "Do": method.name(),
+        });
+        let response = api::send(self.request(Method::POST, &format!("/repos/{}/{}/pulls/{}/merge", owner, name, number)).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(self.user_config, response).await);
+        }
+        Ok(())
+    }
+
+    // issues have the same shape as on GitHub too
+    async fn list_issues(&self, owner: &str, name: &str, filter: &IssueFilter) -> Result<Vec<Issue>, Box<dyn std::error::Error>> {
+        let endpoint = format!("/repos/{}/{}/issues?{}&type=issues&limit=50", owner, name, github::issue_query(filter, "assigned_by"));
+        let issues: Vec<IssueResponse> = self.get_all_pages(&endpoint).await?;
+        Ok(issues.into_iter()
+            .filter(|issue| !issue.is_pull_request())
+            .map(IssueResponse::into_issue)
+            .collect())
+    }
+
+    async fn get_issue(&self, owner: &str, name: &str, number: u64) -> Result<Option<Issue>, Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::GET, &format!("/repos/{}/{}/issues/{}", owner, name, number))).await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(api_error(self.user_config, response).await);
+        }
+        let issue: IssueResponse = response.json().await?;
+        Ok(Some(issue.into_issue()))
+    }
+
+    async fn list_comments(&self, owner: &str, name: &str, number: u64) -> Result<Vec<Comment>, Box<dyn std::error::Error>> {
+        let comments: Vec<CommentResponse> = self.get_all_pages(&format!("/repos/{}/{}/issues/{}/comments", owner, name, number)).await?;
+        Ok(comments.into_iter().map(CommentResponse::into_comment).collect())
+    }
+
+    async fn create_issue(&self, owner: &str, name: &str, title: &str, body: &str) -> Result<Issue, Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "title": title,
+            "body": body,
+        });
+        let response = api::send(self.request(Method::POST, &format!("/repos/{}/{}/issues", owner, name)).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(self.user_config, response).await);
+        }
+        let issue: IssueResponse = response.json().await?;
+        Ok(issue.into_issue())
+    }
+
+    async fn comment_issue(&self, owner: &str, name: &str, number: u64, body: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "body": body,
+        });
+        let response = api::send(self.request(Method::POST, &format!("/repos/{}/{}/issues/{}/comments", owner, name, number)).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(self.user_config, response).await);
+        }
+        Ok(())
+    }
+
+    async fn close_issue(&self, owner: &str, name: &str, number: u64) -> Result<(), Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "state": "closed",
+        });
+        let response = api::send(self.request(Method::PATCH, &format!("/repos/{}/{}/issues/{}", owner, name, number)).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(self.user_config, response).await);
+        }
+        Ok(())
+    }
+
+    async fn create_release(&self, owner: &str, name: &str, tag: &str, title: &str, notes: &str) -> Result<Release, Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "tag_name": tag,
+            "name": title,
+            "body": notes,
+        });
+        let response = api::send(self.request(Method::POST, &format!("/repos/{}/{}/releases", owner, name)).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(self.user_config, response).await);
+        }
+        let release: ReleaseResponse = response.json().await?;
+        Ok(release.into_release())
+    }
+
+    async fn get_release(&self, owner: &str, name: &str, tag: &str) -> Result<Option<Release>, Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::GET, &format!("/repos/{}/{}/releases/tags/{}", owner, name, api::encode(tag)))).await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(api_error(self.user_config, response).await);
+        }
+        let release: ReleaseResponse = response.json().await?;
+        Ok(Some(release.into_release()))
+    }
+
+    async fn list_releases(&self, owner: &str, name: &str) -> Result<Vec<Release>, Box<dyn std::error::Error>> {
+        let releases: Vec<ReleaseResponse> = self.get_all_pages(&format!("/repos/{}/{}/releases?limit=50", owner, name)).await?;
+        Ok(releases.into_iter().map(ReleaseResponse::into_release).collect())
+    }
+
+    async fn delete_release(&self, owner: &str, name: &str, release: &Release) -> Result<(), Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::DELETE, &format!("/repos/{}/{}/releases/{}", owner, name, release.id))).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(self.user_config, response).await);
+        }
+        Ok(())
+    }
+
+    async fn upload_asset(&self, owner: &str, name: &str, release: &Release, file_name: &str, content: Vec<u8>) -> Result<(), Box<dyn std::error::Error>> {
+        let (content_type, body) = api::multipart_file("attachment", file_name, &content);
+        let endpoint = format!("/repos/{}/{}/releases/{}/assets?name={}", owner, name, release.id, api::encode(file_name));
+        let request = self.request(Method::POST, &endpoint)
+            .header(header::CONTENT_TYPE, content_type)
+            .body(body);
+        let response = api::send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(self.user_config, response).await);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,651 @@
+use crate::*;
+use forge::{Checks, Comment, Forge, Head, Issue, IssueFilter, IssueState, MergeMethod, PullRequest, Release, RemoteRepository, RepoOwner, RepoUpdate};
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+
+#[derive(Deserialize)]
+struct ApiErrorBody {
+    message: String,
+    #[serde(default)]
+    errors: Vec<ApiErrorDetail>,
+}
+
+#[derive(Deserialize)]
+struct ApiErrorDetail {
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    field: Option<String>,
+    #[serde(default)]
+    code: Option<String>,
+}
+
+/// A pull request as GitHub and Gitea send it.
+#[derive(Deserialize)]
+pub struct PullResponse {
+    number: u64,
+    title: String,
+    user: forge::Owner,
+    head: PullBranch,
+    base: PullBranch,
+    html_url: String,
+    #[serde(default)]
+    draft: bool,
+}
+
+#[derive(Deserialize)]
+struct PullBranch {
+    #[serde(rename = "ref")]
+    name: String,
+    sha: String,
+}
+
+impl PullResponse {
+    pub fn into_pull_request(self, checks: Checks) -> PullRequest {
+        PullRequest {
+            number: self.number,
+            title: self.title,
+            author: self.user.login,
+            head: self.head.name,
+            head_sha: self.head.sha,
+            base: self.base.name,
+            url: self.html_url,
+            draft: self.draft,
+            checks,
+        }
+    }
+}
+
+/// An issue as GitHub and Gitea send it. Both also list pull requests as issues.
+#[derive(Deserialize)]
+pub struct IssueResponse {
+    number: u64,
+    title: String,
+    body: Option<String>,
+    state: String,
+    user: forge::Owner,
+    #[serde(default)]
+    labels: Option<Vec<Label>>,
+    #[serde(default)]
+    assignees: Option<Vec<forge::Owner>>,
+    #[serde(default)]
+    comments: u64,
+    html_url: String,
+    created_at: String,
+    #[serde(default)]
+    pull_request: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct Label {
+    name: String,
+}
+
+impl IssueResponse {
+    pub fn is_pull_request(&self) -> bool {
+        self.pull_request.is_some()
+    }
+
+    pub fn into_issue(self) -> Issue {
+        Issue {
+            number: self.number,
+            title: self.title,
+            body: self.body.unwrap_or_default(),
+            open: self.state == "open",
+            author: self.user.login,
+            labels: self.labels.unwrap_or_default().into_iter().map(|label| label.name).collect(),
+            assignees: self.assignees.unwrap_or_default().into_iter().map(|assignee| assignee.login).collect(),
+            comments: self.comments,
+            url: self.html_url,
+            created_at: self.created_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CommentResponse {
+    user: forge::Owner,
+    #[serde(default)]
+    body: String,
+    created_at: String,
+}
+
+impl CommentResponse {
+    pub fn into_comment(self) -> Comment {
+        Comment { author: self.user.login, body: self.body, created_at: self.created_at }
+    }
+}
+
+/// The query parameters of an issue list, `assignee_key` is the name the forge uses for the assignee filter.
+pub fn issue_query(filter: &IssueFilter, assignee_key: &str) -> String {
+    let state = match filter.state {
+        IssueState::Open => "open",
+        IssueState::Closed => "closed",
+        IssueState::All => "all",
+    };
+    let mut query = format!("state={}", state);
+    if let Some(assignee) = &filter.assignee {
+        query.push_str(&format!("&{}={}", assignee_key, api::encode(assignee)));
+    }
+    if !filter.labels.is_empty() {
+        let labels = filter.labels.iter().map(|label| api::encode(label)).collect::<Vec<String>>();
+        query.push_str(&format!("&labels={}", labels.join(",")));
+    }
+    query
+}
+
+/// A release as GitHub and Gitea send it.
+#[derive(Deserialize)]
+pub struct ReleaseResponse {
+    id: u64,
+    tag_name: String,
+    name: Option<String>,
+    created_at: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    assets: Vec<Asset>,
+    html_url: String,
+    #[serde(default)]
+    upload_url: String,
+}
+
+#[derive(Deserialize)]
+struct Asset {
+    name: String,
+}
+
+impl ReleaseResponse {
+    pub fn into_release(self) -> Release {
+        Release {
+            id: self.id,
+            name: self.name.filter(|name| !name.is_empty()).unwrap_or_else(|| self.tag_name.clone()),
+            tag: self.tag_name,
+            created_at: self.created_at,
+            draft: self.draft,
+            prerelease: self.prerelease,
+            assets: self.assets.into_iter().map(|asset| asset.name).collect(),
+            url: self.html_url,
+            // https://uploads.github.com/repos/<owner>/<name>/releases/<id>/assets{?name,label}
+            upload_url: self.upload_url.split('{').next().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+/// The commit statuses reported for a commit, combined into one state.
+#[derive(Deserialize)]
+pub struct CombinedStatus {
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    total_count: u64,
+}
+
+impl CombinedStatus {
+    pub fn checks(&self) -> Checks {
+        if self.total_count == 0 {
+            return Checks::Missing;
+        }
+        match self.state.as_str() {
+            "success" => Checks::Passing,
+            "pending" => Checks::Pending,
+            "failure" | "error" | "warning" => Checks::Failing,
+            _ => Checks::Missing,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CheckRuns {
+    check_runs: Vec<CheckRun>,
+}
+
+#[derive(Deserialize)]
+struct CheckRun {
+    status: String,
+    conclusion: Option<String>,
+}
+
+impl CheckRun {
+    fn checks(&self) -> Checks {
+        if self.status != "completed" {
+            return Checks::Pending;
+        }
+        match self.conclusion.as_deref() {
+            Some("success") | Some("neutral") | Some("skipped") => Checks::Passing,
+            Some(_) => Checks::Failing,
+            None => Checks::Pending,
+        }
+    }
+}
+
+pub struct GitHub<'a> {
+    user_config: &'a Config,
+}
+
+impl<'a> GitHub<'a> {
+    pub fn new(user_config: &'a Config) -> Self {
+        GitHub { user_config }
+    }
+
+    fn request(&self, method: Method, endpoint: &str) -> RequestBuilder {
+        let url = format!("{}{}", self.user_config.api_url.trim_end_matches('/'), endpoint);
+        self.authorize(api::request(method, &url))
+    }
+
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        let api_key = &self.user_config.api_key;
+        let mut request = request.header(header::ACCEPT, "application/vnd.github+json");
+
+        if !api_key.trim().is_empty() {
+            request = request.header(header::AUTHORIZATION, format!("token {}", api_key));
+        }
+        request
+    }
+
+    /// Requests `endpoint` and every following page (`Link: rel="next"`) and collects all items.
+    async fn get_all_pages<T: DeserializeOwned>(&self, endpoint: &str) -> Result<Vec<T>, Box<dyn std::error::Error>> {
+        let mut items = Vec::new();
+        let mut next_url = Some(format!("{}{}", self.user_config.api_url.trim_end_matches('/'), endpoint));
+
+        while let Some(url) = next_url {
+            let response = api::send(self.authorize(api::request(Method::GET, &url))).await?;
+            if !response.status().is_success() {
+                return Err(api_error(response).await);
+            }
+            next_url = api::next_page(&response);
+
+            let page: Vec<T> = response.json().await?;
+            items.extend(page);
+        }
+        Ok(items)
+    }
+
+    /// GitHub has two kinds of checks: commit statuses and check runs (Actions), both count.
+    /// Checks the token can't read are left out.
+    async fn checks(&self, owner: &str, name: &str, sha: &str) -> Checks {
+        let mut checks = Vec::new();
+
+        let status = api::send(self.request(Method::GET, &format!("/repos/{}/{}/commits/{}/status", owner, name, sha))).await;
+        if let Ok(response) = status {
+            if response.status().is_success() {
+                if let Ok(status) = response.json::<CombinedStatus>().await {
+                    checks.push(status.checks());
+                }
+            }
+        }
+
+        let runs = api::send(self.request(Method::GET, &format!("/repos/{}/{}/commits/{}/check-runs?per_page=100", owner, name, sha))).await;
+        if let Ok(response) = runs {
+            if response.status().is_success() {
+                if let Ok(runs) = response.json::<CheckRuns>().await {
+                    checks.extend(runs.check_runs.iter().map(CheckRun::checks));
+                }
+            }
+        }
+
+        Checks::combine(checks)
+    }
+}
+
+/// Turns an unsuccessful response into a readable error, including the message GitHub sent
+/// and the token scopes it wanted if they are missing.
+pub async fn api_error(response: Response) -> Box<dyn std::error::Error> {
+    let status = response.status();
+    let accepted_scopes = response.headers().get("x-accepted-oauth-scopes")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    let token_scopes = response.headers().get("x-oauth-scopes")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
+    let text = response.text().await.unwrap_or_default();
+
+    let mut message = format!("GitHub answered {}", status);
+    match serde_json::from_str::<ApiErrorBody>(&text) {
+        Ok(body) => {
+            message.push_str(&format!(": {}", body.message));
+            for detail in body.errors {
+                let detail = detail.message
+                    .or_else(|| detail.field.map(|field| format!("{} {}", field, detail.code.unwrap_or_default())));
+                if let Some(detail) = detail {
+                    message.push_str(&format!(" ({})", detail));
+                }
+            }
+        }
+        Err(_) if !text.trim().is_empty() => message.push_str(&format!(": {}", text.trim())),
+        Err(_) => {}
+    }
+
+    if let Some(token_scopes) = token_scopes {
+        let missing = accepted_scopes.split(',')
+            .map(|scope| scope.trim())
+            .filter(|scope| !scope.is_empty() && !token_scopes.split(',').any(|owned| owned.trim() == *scope))
+            .collect::<Vec<&str>>();
+        if (status == StatusCode::FORBIDDEN || status == StatusCode::NOT_FOUND) && !missing.is_empty() {
+            message.push_str(&format!(" - your token is missing the scope(s): {}", missing.join(", ")));
+        }
+    }
+
+    message.into()
+}
+
+#[derive(Deserialize)]
+struct RateLimitResponse {
+    resources: std::collections::HashMap<String, RateLimitResource>,
+}
+
+#[derive(Deserialize)]
+struct RateLimitResource {
+    limit: u64,
+    remaining: u64,
+    reset: u64,
+}
+
+impl Forge for GitHub<'_> {
+    fn user_config(&self) -> &Config {
+        self.user_config
+    }
+
+    async fn get_repository(&self, owner: &str, name: &str) -> Result<Option<RemoteRepository>, Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::GET, &format!("/repos/{}/{}", owner, name))).await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(Some(response.json().await?))
+    }
+
+    async fn create_repository(&self, name: &str, public: bool) -> Result<RemoteRepository, Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "name": name,
+            "private": !public,
+        });
+        let response = api::send(self.request(Method::POST, "/user/repos").json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(response.json().await?)
+    }
+
+    async fn delete_repository(&self, owner: &str, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::DELETE, &format!("/repos/{}/{}", owner, name))).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(())
+    }
+
+    /// Answers 202 right away, the git data is copied afterwards.
+    async fn fork_repository(&self, owner: &str, name: &str) -> Result<RemoteRepository, Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::POST, &format!("/repos/{}/{}/forks", owner, name))).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(response.json().await?)
+    }
+
+    async fn update_repository(&self, owner: &str, name: &str, update: &RepoUpdate) -> Result<RemoteRepository, Box<dyn std::error::Error>> {
+        let mut body = serde_json::Map::new();
+        if let Some(new_name) = &update.name {
+            body.insert("name".to_string(), serde_json::json!(new_name));
+        }
+        if let Some(private) = update.private {
+            body.insert("private".to_string(), serde_json::json!(private));
+        }
+        if let Some(description) = &update.description {
+            body.insert("description".to_string(), serde_json::json!(description));
+        }
+        if let Some(archived) = update.archived {
+            body.insert("archived".to_string(), serde_json::json!(archived));
+        }
+        let response = api::send(self.request(Method::PATCH, &format!("/repos/{}/{}", owner, name)).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(response.json().await?)
+    }
+
+    async fn set_topics(&self, owner: &str, name: &str, topics: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "names": topics,
+        });
+        let response = api::send(self.request(Method::PUT, &format!("/repos/{}/{}/topics", owner, name)).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(())
+    }
+
+    async fn list_repositories(&self, owner: RepoOwner<'_>) -> Result<Vec<CloneData>, Box<dyn std::error::Error>> {
+        let endpoint = match owner {
+            // /users/<name>/repos only shows public repositories, even for the owner
+            RepoOwner::Own => "/user/repos?per_page=100&affiliation=owner".to_string(),
+            RepoOwner::User(user) => format!("/users/{}/repos?per_page=100", user),
+            RepoOwner::Group(org) => format!("/orgs/{}/repos?per_page=100&type=all", org),
+        };
+        self.get_all_pages(&endpoint).await
+    }
+
+    async fn branch_exists(&self, owner: &str, name: &str, branch: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::GET, &format!("/repos/{}/{}/branches/{}", owner, name, api::encode(branch)))).await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(true)
+    }
+
+    async fn delete_branch(&self, owner: &str, name: &str, branch: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::DELETE, &format!("/repos/{}/{}/git/refs/heads/{}", owner, name, api::encode(branch)))).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(())
+    }
+
+    /// Shows how many API requests are left. Asking doesn't count against the limit.
+    async fn print_rate_limit(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::GET, "/rate_limit")).await?;
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        let rate_limit: RateLimitResponse = response.json().await?;
+
+        for name in ["core", "search", "graphql"] {
+            if let Some(resource) = rate_limit.resources.get(name) {
+                let remaining = format!("{}/{}", resource.remaining, resource.limit);
+                let remaining = if resource.remaining == 0 { remaining.red().bold() } else { remaining.green() };
+                println!("{:<8} {} requests left, resets {}", name.blue().bold(), remaining, api::format_reset(resource.reset));
+            }
+        }
+        Ok(())
+    }
+
+    async fn create_pull_request(&self, owner: &str, name: &str, head: &Head<'_>, base: &str, title: &str, body: &str) -> Result<PullRequest, Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "title": title,
+            "body": body,
+            "head": head.qualified(owner, name),
+            "base": base,
+        });
+        let response = api::send(self.request(Method::POST, &format!("/repos/{}/{}/pulls", owner, name)).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        let pull: PullResponse = response.json().await?;
+        Ok(pull.into_pull_request(Checks::Missing))
+    }
+
+    async fn get_pull_request(&self, owner: &str, name: &str, number: u64) -> Result<Option<PullRequest>, Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::GET, &format!("/repos/{}/{}/pulls/{}", owner, name, number))).await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        let pull: PullResponse = response.json().await?;
+        let checks = self.checks(owner, name, &pull.head.sha).await;
+        Ok(Some(pull.into_pull_request(checks)))
+    }
+
+    async fn list_pull_requests(&self, owner: &str, name: &str) -> Result<Vec<PullRequest>, Box<dyn std::error::Error>> {
+        let pulls: Vec<PullResponse> = self.get_all_pages(&format!("/repos/{}/{}/pulls?state=open&per_page=100", owner, name)).await?;
+
+        let mut pull_requests = Vec::new();
+        for pull in pulls {
+            let checks = self.checks(owner, name, &pull.head.sha).await;
+            pull_requests.push(pull.into_pull_request(checks));
+        }
+        Ok(pull_requests)
+    }
+
+    async fn merge_pull_request(&self, owner: &str, name: &str, number: u64, method: MergeMethod) -> Result<(), Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "merge_method": method.name(),
+        });
+        let response = api::send(self.request(Method::PUT, &format!("/repos/{}/{}/pulls/{}/merge", owner, name, number)).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(())
+    }
+
+    async fn list_issues(&self, owner: &str, name: &str, filter: &IssueFilter) -> Result<Vec<Issue>, Box<dyn std::error::Error>> {
+        let endpoint = format!("/repos/{}/{}/issues?{}&per_page=100", owner, name, issue_query(filter, "assignee"));
+        let issues: Vec<IssueResponse> = self.get_all_pages(&endpoint).await?;
+        Ok(issues.into_iter()
+            .filter(|issue| !issue.is_pull_request())
+            .map(IssueResponse::into_issue)
+            .collect())
+    }
+
+    async fn get_issue(&self, owner: &str, name: &str, number: u64) -> Result<Option<Issue>, Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::GET, &format!("/repos/{}/{}/issues/{}", owner, name, number))).await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        let issue: IssueResponse = response.json().await?;
+        Ok(Some(issue.into_issue()))
+    }
+
+    async fn list_comments(&self, owner: &str, name: &str, number: u64) -> Result<Vec<Comment>, Box<dyn std::error::Error>> {
+        let comments: Vec<CommentResponse> = self.get_all_pages(&format!("/repos/{}/{}/issues/{}/comments?per_page=100", owner, name, number)).await?;
+        Ok(comments.into_iter().map(CommentResponse::into_comment).collect())
+    }
+
+    async fn create_issue(&self, owner: &str, name: &str, title: &str, body: &str) -> Result<Issue, Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "title": title,
+            "body": body,
+        });
+        let response = api::send(self.request(Method::POST, &format!("/repos/{}/{}/issues", owner, name)).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        let issue: IssueResponse = response.json().await?;
+        Ok(issue.into_issue())
+    }
+
+    async fn comment_issue(&self, owner: &str, name: &str, number: u64, body: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "body": body,
+        });
+        let response = api::send(self.request(Method::POST, &format!("/repos/{}/{}/issues/{}/comments", owner, name, number)).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(())
+    }
+
+    async fn close_issue(&self, owner: &str, name: &str, number: u64) -> Result<(), Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "state": "closed",
+        });
+        let response = api::send(self.request(Method::PATCH, &format!("/repos/{}/{}/issues/{}", owner, name, number)).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(())
+    }
+
+    async fn create_release(&self, owner: &str, name: &str, tag: &str, title: &str, notes: &str) -> Result<Release, Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "tag_name": tag,
+            "name": title,
+            "body": notes,
+        });
+        let response = api::send(self.request(Method::POST, &format!("/repos/{}/{}/releases", owner, name)).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        let release: ReleaseResponse = response.json().await?;
+        Ok(release.into_release())
+    }
+
+    async fn get_release(&self, owner: &str, name: &str, tag: &str) -> Result<Option<Release>, Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::GET, &format!("/repos/{}/{}/releases/tags/{}", owner, name, api::encode(tag)))).await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        let release: ReleaseResponse = response.json().await?;
+        Ok(Some(release.into_release()))
+    }
+
+    async fn list_releases(&self, owner: &str, name: &str) -> Result<Vec<Release>, Box<dyn std::error::Error>> {
+        let releases: Vec<ReleaseResponse> = self.get_all_pages(&format!("/repos/{}/{}/releases?per_page=100", owner, name)).await?;
+        Ok(releases.into_iter().map(ReleaseResponse::into_release).collect())
+    }
+
+    async fn delete_release(&self, owner: &str, name: &str, release: &Release) -> Result<(), Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::DELETE, &format!("/repos/{}/{}/releases/{}", owner, name, release.id))).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(())
+    }
+
+    async fn upload_asset(&self, _owner: &str, _name: &str, release: &Release, file_name: &str, content: Vec<u8>) -> Result<(), Box<dyn std::error::Error>> {
+        let url = format!("{}?name={}", release.upload_url, api::encode(file_name));
+        let request = self.authorize(api::request(Method::POST, &url))
+            .header(header::CONTENT_TYPE, "application/octet-stream")
+            .body(content);
+        let response = api::send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(())
+    }
+}
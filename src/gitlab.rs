@@ -0,0 +1,722 @@
+use crate::*;
+use forge::{Checks, Comment, Forge, Head, Issue, IssueFilter, IssueState, MergeMethod, Owner, Permissions, PullRequest, Release, RemoteRepository, RepoOwner, RepoUpdate};
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+
+// GitLab access levels
+const REPORTER: u32 = 20;
+const DEVELOPER: u32 = 30;
+const OWNER: u32 = 50;
+
+#[derive(Deserialize)]
+struct Project {
+    id: u64,
+    path: String,
+    path_with_namespace: String,
+    http_url_to_repo: String,
+    ssh_url_to_repo: String,
+    web_url: String,
+    #[serde(default)]
+    visibility: Option<String>,
+    // missing for empty repositories
+    default_branch: Option<String>,
+    namespace: Namespace,
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    forked_from_project: Option<serde_json::Value>,
+    #[serde(default)]
+    star_count: u32,
+    last_activity_at: Option<String>,
+    #[serde(default)]
+    topics: Vec<String>,
+    #[serde(default)]
+    permissions: Option<ProjectPermissions>,
+}
+
+#[derive(Deserialize)]
+struct Namespace {
+    full_path: String,
+}
+
+#[derive(Deserialize)]
+struct ProjectPermissions {
+    project_access: Option<Access>,
+    group_access: Option<Access>,
+}
+
+#[derive(Deserialize)]
+struct Access {
+    access_level: u32,
+}
+
+impl Project {
+    fn private(&self) -> bool {
+        self.visibility.as_deref() != Some("public")
+    }
+
+    fn into_remote(self) -> RemoteRepository {
+        let private = self.private();
+        let parent = self.forked_from_project
+            .and_then(|parent| serde_json::from_value::<Project>(parent).ok())
+            .map(|parent| Box::new(parent.into_remote()));
+        // the higher of the project and the group access counts
+        let permissions = self.permissions.map(|permissions| {
+            let level = [permissions.project_access, permissions.group_access].into_iter()
+                .flatten()
+                .map(|access| access.access_level)
+                .max()
+                .unwrap_or(0);
+            Permissions { admin: level >= OWNER, push: level >= DEVELOPER, pull: level >= REPORTER }
+        });
+
+        RemoteRepository {
+            name: self.path,
+            full_name: self.path_with_namespace,
+            clone_url: self.http_url_to_repo,
+            ssh_url: self.ssh_url_to_repo,
+            html_url: self.web_url,
+            private,
+            default_branch: self.default_branch.unwrap_or_else(|| "main".to_string()),
+            owner: Owner { login: self.namespace.full_path },
+            permissions,
+            parent,
+        }
+    }
+
+    fn into_clone_data(self) -> CloneData {
+        CloneData {
+            private: self.private(),
+            clone_url: self.http_url_to_repo,
+            ssh_url: self.ssh_url_to_repo,
+            name: self.path,
+            fork: self.forked_from_project.is_some(),
+            archived: self.archived,
+            // the project list doesn't include languages
+            language: None,
+            stargazers_count: self.star_count,
+            pushed_at: self.last_activity_at,
+            topics: self.topics,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct MergeRequest {
+    iid: u64,
+    title: String,
+    author: User,
+    source_branch: String,
+    target_branch: String,
+    #[serde(default)]
+    sha: Option<String>,
+    web_url: String,
+    #[serde(default)]
+    draft: bool,
+}
+
+#[derive(Deserialize)]
+struct User {
+    username: String,
+}
+
+#[derive(Deserialize)]
+struct Pipeline {
+    status: String,
+}
+
+impl MergeRequest {
+    fn into_pull_request(self, checks: Checks) -> PullRequest {
+        PullRequest {
+            number: self.iid,
+            title: self.title,
+            author: self.author.username,
+            head: self.source_branch,
+            head_sha: self.sha.unwrap_or_default(),
+            base: self.target_branch,
+            url: self.web_url,
+            draft: self.draft,
+            checks,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GitLabIssue {
+    iid: u64,
+    title: String,
+    description: Option<String>,
+    state: String, // "opened" or "closed"
+    author: User,
+    #[serde(default)]
+    labels: Vec<String>,
+    #[serde(default)]
+    assignees: Vec<User>,
+    #[serde(default)]
+    user_notes_count: u64,
+    web_url: String,
+    created_at: String,
+}
+
+impl GitLabIssue {
+    fn into_issue(self) -> Issue {
+        Issue {
+            number: self.iid,
+            title: self.title,
+            body: self.description.unwrap_or_default(),
+            open: self.state == "opened",
+            author: self.author.username,
+            labels: self.labels,
+            assignees: self.assignees.into_iter().map(|assignee| assignee.username).collect(),
+            comments: self.user_notes_count,
+            url: self.web_url,
+            created_at: self.created_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Note {
+    author: User,
+    body: String,
+    created_at: String,
+    // "changed the description" and similar, not written by anyone
+    #[serde(default)]
+    system: bool,
+}
+
+#[derive(Deserialize)]
+struct GitLabRelease {
+    tag_name: String,
+    name: Option<String>,
+    created_at: String,
+    // released_at lies in the future
+    #[serde(default)]
+    upcoming_release: bool,
+    assets: ReleaseAssets,
+    #[serde(rename = "_links")]
+    links: ReleaseLinks,
+}
+
+#[derive(Deserialize)]
+struct ReleaseAssets {
+    #[serde(default)]
+    links: Vec<AssetLink>,
+}
+
+#[derive(Deserialize)]
+struct AssetLink {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct ReleaseLinks {
+    #[serde(rename = "self")]
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct UploadedFile {
+    url: String,
+    // newer GitLab versions, already including the project
+    full_path: Option<String>,
+}
+
+impl GitLabRelease {
+    fn into_release(self) -> Release {
+        Release {
+            id: 0,
+            name: self.name.filter(|name| !name.is_empty()).unwrap_or_else(|| self.tag_name.clone()),
+            tag: self.tag_name,
+            created_at: self.created_at,
+            draft: false,
+            prerelease: self.upcoming_release,
+            assets: self.assets.links.into_iter().map(|link| link.name).collect(),
+            url: self.links.url,
+            upload_url: String::new(),
+        }
+    }
+}
+
+/// GitLab API v4, api_url is https://<host>/api/v4 and the api key a personal access token.
+pub struct GitLab<'a> {
+    user_config: &'a Config,
+}
+
+impl<'a> GitLab<'a> {
+    pub fn new(user_config: &'a Config) -> Self {
+        GitLab { user_config }
+    }
+
+    fn request(&self, method: Method, endpoint: &str) -> RequestBuilder {
+        let url = format!("{}{}", self.user_config.api_url.trim_end_matches('/'), endpoint);
+        self.authorize(api::request(method, &url))
+    }
+
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        let api_key = &self.user_config.api_key;
+        if api_key.trim().is_empty() {
+            return request;
+        }
+        request.header("PRIVATE-TOKEN", api_key.as_str())
+    }
+
+    /// Requests `endpoint` and every following page (`Link: rel="next"`) and collects all items.
+    async fn get_all_pages<T: DeserializeOwned>(&self, endpoint: &str) -> Result<Vec<T>, Box<dyn std::error::Error>> {
+        let mut items = Vec::new();
+        let mut next_url = Some(format!("{}{}", self.user_config.api_url.trim_end_matches('/'), endpoint));
+
+        while let Some(url) = next_url {
+            let response = api::send(self.authorize(api::request(Method::GET, &url))).await?;
+            if !response.status().is_success() {
+                return Err(api_error(response).await);
+            }
+            next_url = api::next_page(&response);
+
+            let page: Vec<T> = response.json().await?;
+            items.extend(page);
+        }
+        Ok(items)
+    }
+
+    /// The state of the newest pipeline of the merge request.
+    async fn checks(&self, project: &str, iid: u64) -> Checks {
+        let response = match api::send(self.request(Method::GET, &format!("/projects/{}/merge_requests/{}/pipelines", project, iid))).await {
+            Ok(response) if response.status().is_success() => response,
+            _ => return Checks::Missing,
+        };
+        let pipelines: Vec<Pipeline> = response.json().await.unwrap_or_default();
+        match pipelines.first().map(|pipeline| pipeline.status.as_str()) {
+            Some("success") => Checks::Passing,
+            Some("failed") | Some("canceled") => Checks::Failing,
+            Some("skipped") | Some("manual") | None => Checks::Missing,
+            Some(_) => Checks::Pending,
+        }
+    }
+}
+
+/// Projects and groups can be addressed by their url encoded path instead of the numeric id.
+pub fn project_id(owner: &str, name: &str) -> String {
+    api::encode(&format!("{}/{}", owner, name))
+}
+
+/// Turns an unsuccessful response into a readable error. GitLab sends `message` as a string,
+/// as an object of field errors or as a list, OAuth errors come as `error`.
+pub async fn api_error(response: Response) -> Box<dyn std::error::Error> {
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+
+    let mut message = format!("GitLab answered {}", status);
+    let body = serde_json::from_str::<serde_json::Value>(&text).ok();
+    let detail = body.as_ref().and_then(|body| {
+        let detail = match body.get("message").or_else(|| body.get("error")) {
+            Some(serde_json::Value::String(text)) => text.clone(),
+            Some(serde_json::Value::Array(messages)) => messages.iter().map(value_text).collect::<Vec<String>>().join(", "),
+            Some(serde_json::Value::Object(fields)) => fields.iter()
+                .map(|(field, messages)| format!("{} {}", field, value_text(messages)))
+                .collect::<Vec<String>>()
+                .join(", "),
+            _ => return None,
+        };
+        Some(detail)
+    });
+
+    match detail {
+        Some(detail) => message.push_str(&format!(": {}", detail)),
+        None if !text.trim().is_empty() && body.is_none() => message.push_str(&format!(": {}", text.trim())),
+        None => {}
+    }
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        message.push_str(" - check that your token has the 'api' scope");
+    }
+    message.into()
+}
+
+fn value_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Array(values) => values.iter().map(value_text).collect::<Vec<String>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+impl Forge for GitLab<'_> {
+    fn user_config(&self) -> &Config {
+        self.user_config
+    }
+
+    async fn get_repository(&self, owner: &str, name: &str) -> Result<Option<RemoteRepository>, Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::GET, &format!("/projects/{}", project_id(owner, name)))).await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        let project: Project = response.json().await?;
+        Ok(Some(project.into_remote()))
+    }
+
+    /// Creates the project in the namespace of the token's user.
+    async fn create_repository(&self, name: &str, public: bool) -> Result<RemoteRepository, Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "name": name,
+            "path": name,
+            "visibility": if public { "public" } else { "private" },
+        });
+        let response = api::send(self.request(Method::POST, "/projects").json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        let project: Project = response.json().await?;
+        Ok(project.into_remote())
+    }
+
+    async fn delete_repository(&self, owner: &str, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::DELETE, &format!("/projects/{}", project_id(owner, name)))).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(())
+    }
+
+    /// The fork is imported in the background, its import_status says when it's done.
+    async fn fork_repository(&self, owner: &str, name: &str) -> Result<RemoteRepository, Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::POST, &format!("/projects/{}/fork", project_id(owner, name)))).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        let project: Project = response.json().await?;
+        Ok(project.into_remote())
+    }
+
+    /// Archiving has its own endpoints on GitLab, everything else is one update.
+    async fn update_repository(&self, owner: &str, name: &str, update: &RepoUpdate) -> Result<RemoteRepository, Box<dyn std::error::Error>> {
+        let project = project_id(owner, name);
+        let mut body = serde_json::Map::new();
+        if let Some(new_name) = &update.name {
+            body.insert("name".to_string(), serde_json::json!(new_name));
+            body.insert("path".to_string(), serde_json::json!(new_name));
+        }
+        if let Some(private) = update.private {
+            body.insert("visibility".to_string(), serde_json::json!(if private { "private" } else { "public" }));
+        }
+        if let Some(description) = &update.description {
+            body.insert("description".to_string(), serde_json::json!(description));
+        }
+
+        let mut response = None;
+        if !body.is_empty() {
+            response = Some(api::send(self.request(Method::PUT, &format!("/projects/{}", project)).json(&body)).await?);
+        }
+        if let Some(archived) = update.archived {
+            let action = if archived { "archive" } else { "unarchive" };
+            response = Some(api::send(self.request(Method::POST, &format!("/projects/{}/{}", project, action))).await?);
+        }
+
+        let response = match response {
+            Some(response) => response,
+            None => api::send(self.request(Method::GET, &format!("/projects/{}", project))).await?,
+        };
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        let project: Project = response.json().await?;
+        Ok(project.into_remote())
+    }
+
+    async fn set_topics(&self, owner: &str, name: &str, topics: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "topics": topics,
+        });
+        let response = api::send(self.request(Method::PUT, &format!("/projects/{}", project_id(owner, name))).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(())
+    }
+
+    async fn list_repositories(&self, owner: RepoOwner<'_>) -> Result<Vec<CloneData>, Box<dyn std::error::Error>> {
+        let endpoint = match owner {
+            RepoOwner::Own => "/projects?owned=true&per_page=100".to_string(),
+            RepoOwner::User(user) => format!("/users/{}/projects?per_page=100", api::encode(user)),
+            RepoOwner::Group(group) => format!("/groups/{}/projects?per_page=100&include_subgroups=true", api::encode(group)),
+        };
+        let projects: Vec<Project> = self.get_all_pages(&endpoint).await?;
+        Ok(projects.into_iter().map(Project::into_clone_data).collect())
+    }
+
+    async fn branch_exists(&self, owner: &str, name: &str, branch: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        let endpoint = format!("/projects/{}/repository/branches/{}", project_id(owner, name), api::encode(branch));
+        let response = api::send(self.request(Method::GET, &endpoint)).await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(true)
+    }
+
+    async fn delete_branch(&self, owner: &str, name: &str, branch: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let endpoint = format!("/projects/{}/repository/branches/{}", project_id(owner, name), api::encode(branch));
+        let response = api::send(self.request(Method::DELETE, &endpoint)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(())
+    }
+
+    /// GitLab has no rate limit endpoint, so this makes a cheap request and shows its headers.
+    async fn print_rate_limit(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::GET, "/version")).await?;
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+
+        // the headers of this response, not whatever was requested last
+        match api::read_rate_limit(response.headers()) {
+            Some(rate_limit) => {
+                let remaining = format!("{}/{}", rate_limit.remaining, rate_limit.limit);
+                let remaining = if rate_limit.remaining == 0 { remaining.red().bold() } else { remaining.green() };
+                println!("{:<8} {} requests left, resets {}", "api".blue().bold(), remaining, api::format_reset(rate_limit.reset));
+            }
+            None => println!("This GitLab instance doesn't report a rate limit"),
+        }
+        Ok(())
+    }
+
+    /// Merge requests from a fork are created in the fork and point to the numeric id of the target.
+    async fn create_pull_request(&self, owner: &str, name: &str, head: &Head<'_>, base: &str, title: &str, body: &str) -> Result<PullRequest, Box<dyn std::error::Error>> {
+        let mut body = serde_json::json!({
+            "source_branch": head.branch,
+            "target_branch": base,
+            "title": title,
+            "description": body,
+        });
+        let same_project = head.is_in(owner, name);
+        if !same_project {
+            let response = api::send(self.request(Method::GET, &format!("/projects/{}", project_id(owner, name)))).await?;
+            if !response.status().is_success() {
+                return Err(api_error(response).await);
+            }
+            let target: Project = response.json().await?;
+            body["target_project_id"] = serde_json::json!(target.id);
+        }
+        let source = if same_project { project_id(owner, name) } else { api::encode(head.repo) };
+        let response = api::send(self.request(Method::POST, &format!("/projects/{}/merge_requests", source)).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        let merge_request: MergeRequest = response.json().await?;
+        Ok(merge_request.into_pull_request(Checks::Missing))
+    }
+
+    async fn get_pull_request(&self, owner: &str, name: &str, number: u64) -> Result<Option<PullRequest>, Box<dyn std::error::Error>> {
+        let project = project_id(owner, name);
+        let response = api::send(self.request(Method::GET, &format!("/projects/{}/merge_requests/{}", project, number))).await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        let merge_request: MergeRequest = response.json().await?;
+        let checks = self.checks(&project, number).await;
+        Ok(Some(merge_request.into_pull_request(checks)))
+    }
+
+    async fn list_pull_requests(&self, owner: &str, name: &str) -> Result<Vec<PullRequest>, Box<dyn std::error::Error>> {
+        let project = project_id(owner, name);
+        let merge_requests: Vec<MergeRequest> = self.get_all_pages(&format!("/projects/{}/merge_requests?state=opened&per_page=100", project)).await?;
+
+        let mut pull_requests = Vec::new();
+        for merge_request in merge_requests {
+            let checks = self.checks(&project, merge_request.iid).await;
+            pull_requests.push(merge_request.into_pull_request(checks));
+        }
+        Ok(pull_requests)
+    }
+
+    /// GitLab merges with the method set in the project, only squashing can be chosen per merge request.
+    async fn merge_pull_request(&self, owner: &str, name: &str, number: u64, method: MergeMethod) -> Result<(), Box<dyn std::error::Error>> {
+        if method == MergeMethod::Rebase {
+            return Err("GitLab rebases or merges depending on the project settings, use --merge or --squash".into());
+        }
+        let body = serde_json::json!({
+            "squash": method == MergeMethod::Squash,
+        });
+        let endpoint = format!("/projects/{}/merge_requests/{}/merge", project_id(owner, name), number);
+        let response = api::send(self.request(Method::PUT, &endpoint).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(())
+    }
+
+    async fn list_issues(&self, owner: &str, name: &str, filter: &IssueFilter) -> Result<Vec<Issue>, Box<dyn std::error::Error>> {
+        let mut query = vec!["per_page=100".to_string()];
+        match filter.state {
+            IssueState::Open => query.push("state=opened".to_string()),
+            IssueState::Closed => query.push("state=closed".to_string()),
+            IssueState::All => {}
+        }
+        if let Some(assignee) = &filter.assignee {
+            query.push(format!("assignee_username={}", api::encode(assignee)));
+        }
+        if !filter.labels.is_empty() {
+            let labels = filter.labels.iter().map(|label| api::encode(label)).collect::<Vec<String>>();
+            query.push(format!("labels={}", labels.join(",")));
+        }
+
+        let issues: Vec<GitLabIssue> = self.get_all_pages(&format!("/projects/{}/issues?{}", project_id(owner, name), query.join("&"))).await?;
+        Ok(issues.into_iter().map(GitLabIssue::into_issue).collect())
+    }
+
+    async fn get_issue(&self, owner: &str, name: &str, number: u64) -> Result<Option<Issue>, Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::GET, &format!("/projects/{}/issues/{}", project_id(owner, name), number))).await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        let issue: GitLabIssue = response.json().await?;
+        Ok(Some(issue.into_issue()))
+    }
+
+    async fn list_comments(&self, owner: &str, name: &str, number: u64) -> Result<Vec<Comment>, Box<dyn std::error::Error>> {
+        let endpoint = format!("/projects/{}/issues/{}/notes?sort=asc&per_page=100", project_id(owner, name), number);
+        let notes: Vec<Note> = self.get_all_pages(&endpoint).await?;
+        Ok(notes.into_iter()
+            .filter(|note| !note.system)
+            .map(|note| Comment { author: note.author.username, body: note.body, created_at: note.created_at })
+            .collect())
+    }
+
+    async fn create_issue(&self, owner: &str, name: &str, title: &str, body: &str) -> Result<Issue, Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "title": title,
+            "description": body,
+        });
+        let response = api::send(self.request(Method::POST, &format!("/projects/{}/issues", project_id(owner, name))).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        let issue: GitLabIssue = response.json().await?;
+        Ok(issue.into_issue())
+    }
+
+    async fn comment_issue(&self, owner: &str, name: &str, number: u64, body: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "body": body,
+        });
+        let response = api::send(self.request(Method::POST, &format!("/projects/{}/issues/{}/notes", project_id(owner, name), number)).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(())
+    }
+
+    async fn close_issue(&self, owner: &str, name: &str, number: u64) -> Result<(), Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "state_event": "close",
+        });
+        let response = api::send(self.request(Method::PUT, &format!("/projects/{}/issues/{}", project_id(owner, name), number)).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(())
+    }
+
+    async fn create_release(&self, owner: &str, name: &str, tag: &str, title: &str, notes: &str) -> Result<Release, Box<dyn std::error::Error>> {
+        let body = serde_json::json!({
+            "tag_name": tag,
+            "name": title,
+            "description": notes,
+        });
+        let response = api::send(self.request(Method::POST, &format!("/projects/{}/releases", project_id(owner, name))).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        let release: GitLabRelease = response.json().await?;
+        Ok(release.into_release())
+    }
+
+    async fn get_release(&self, owner: &str, name: &str, tag: &str) -> Result<Option<Release>, Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::GET, &format!("/projects/{}/releases/{}", project_id(owner, name), api::encode(tag)))).await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        let release: GitLabRelease = response.json().await?;
+        Ok(Some(release.into_release()))
+    }
+
+    async fn list_releases(&self, owner: &str, name: &str) -> Result<Vec<Release>, Box<dyn std::error::Error>> {
+        let releases: Vec<GitLabRelease> = self.get_all_pages(&format!("/projects/{}/releases?per_page=100", project_id(owner, name))).await?;
+        Ok(releases.into_iter().map(GitLabRelease::into_release).collect())
+    }
+
+    async fn delete_release(&self, owner: &str, name: &str, release: &Release) -> Result<(), Box<dyn std::error::Error>> {
+        let response = api::send(self.request(Method::DELETE, &format!("/projects/{}/releases/{}", project_id(owner, name), api::encode(&release.tag)))).await?;
+
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(())
+    }
+
+    /// GitLab releases only link to files, so the file is uploaded to the project first and then linked.
+    async fn upload_asset(&self, owner: &str, name: &str, release: &Release, file_name: &str, content: Vec<u8>) -> Result<(), Box<dyn std::error::Error>> {
+        let project = project_id(owner, name);
+        let (content_type, body) = api::multipart_file("file", file_name, &content);
+        let request = self.request(Method::POST, &format!("/projects/{}/uploads", project))
+            .header(header::CONTENT_TYPE, content_type)
+            .body(body);
+        let response = api::send(request).await?;
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        let uploaded: UploadedFile = response.json().await?;
+
+        let web_url = self.user_config.web_url.trim_end_matches('/');
+        let url = match uploaded.full_path {
+            Some(full_path) => format!("{}{}", web_url, full_path),
+            None => format!("{}/{}/{}{}", web_url, owner, name, uploaded.url),
+        };
+        let link = serde_json::json!({
+            "name": file_name,
+            "url": url,
+            "link_type": "package",
+        });
+        let endpoint = format!("/projects/{}/releases/{}/assets/links", project, api::encode(&release.tag));
+        let response = api::send(self.request(Method::POST, &endpoint).json(&link)).await?;
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(())
+    }
+
+    fn pull_request_ref(&self, number: u64) -> String {
+        format!("refs/merge-requests/{}/head", number)
+    }
+}
@@ -0,0 +1,240 @@
+use crate::*;
+use forge::{Issue, IssueFilter, IssueState};
+
+/// Prints the issues of the opened repository that match the filter.
+pub async fn list(repoPath: &str, filter: &IssueFilter, user_config: &Config) {
+    let (owner, name) = full_name(repoPath, user_config);
+
+    let issues = match forge::list_issues(user_config, &owner, &name, filter).await {
+        Ok(issues) => issues,
+        Err(error) => {
+            command_line::throw_error(format!("Failed to get the issues of '{}/{}': {}", owner, name, error).as_str());
+            return;
+        }
+    };
+    if issues.is_empty() {
+        println!("No matching issues in {}/{}", owner, name);
+        return;
+    }
+
+    let max_title_length = issues.iter().map(|issue| issue.title.chars().count()).max().unwrap_or(0);
+    for issue in &issues {
+        println!(
+            "{:<5} {}{}  {}  {}  {}{}",
+            format!("#{}", issue.number).blue().bold(),
+            issue.title,
+            " ".repeat(max_title_length - issue.title.chars().count()),
+            state_label(issue),
+            issue.labels.join(", ").yellow(),
+            issue.assignees.iter().map(|assignee| format!("@{}", assignee)).collect::<Vec<String>>().join(" ").green(),
+            if issue.comments > 0 { format!(" ({} comments)", issue.comments).italic().to_string() } else { String::new() },
+        );
+    }
+    println!("{} issue(s)", issues.len());
+}
+
+/// Prints an issue with its description and all comments.
+pub async fn show(repoPath: &str, number: u64, user_config: &Config) {
+    let (owner, name) = full_name(repoPath, user_config);
+    let issue = match find_issue(&owner, &name, number, user_config).await {
+        Some(issue) => issue,
+        None => return,
+    };
+
+    println!("{} {}", format!("#{}", issue.number).blue().bold(), issue.title.bold());
+    println!("{} by {} on {}", state_label(&issue), issue.author, date(&issue.created_at));
+    if !issue.labels.is_empty() {
+        println!("{}: {}", "Labels".underline(), issue.labels.join(", ").yellow());
+    }
+    if !issue.assignees.is_empty() {
+        println!("{}: {}", "Assignees".underline(), issue.assignees.join(", "));
+    }
+    println!("{}", issue.url.italic());
+    println!();
+    if issue.body.trim().is_empty() {
+        println!("{}", "No description".italic());
+    }
+    else {
+        println!("{}", issue.body.trim());
+    }
+
+    if issue.comments == 0 {
+        return;
+    }
+    match forge::list_comments(user_config, &owner, &name, number).await {
+        Ok(comments) => {
+            for comment in comments {
+                println!();
+                println!("{} on {}:", comment.author.blue().bold(), date(&comment.created_at));
+                for line in comment.body.trim().lines() {
+                    println!("  {}", line);
+                }
+            }
+        }
+        Err(error) => command_line::throw_error(format!("Failed to get the comments of #{}: {}", number, error).as_str()),
+    }
+}
+
+/// Opens a new issue. Without an inline body the description is written in $EDITOR.
+pub async fn create(repoPath: &str, title: &str, body: Option<String>, user_config: &Config) {
+    let (owner, name) = full_name(repoPath, user_config);
+    let body = match body.or_else(|| command_line::compose(format!("Description of '{}' (optional)", title).as_str())) {
+        Some(body) => body,
+        None => {
+            println!("The issue was not opened");
+            return;
+        }
+    };
+
+    match forge::create_issue(user_config, &owner, &name, title, &body).await {
+        Ok(issue) => println!("{} #{} '{}': {}", "Opened".green().bold(), issue.number, issue.title, issue.url),
+        Err(error) => command_line::throw_error(format!("Failed to open the issue: {}", error).as_str()),
+    }
+}
+
+pub async fn comment(repoPath: &str, number: u64, body: Option<String>, user_config: &Config) {
+    let (owner, name) = full_name(repoPath, user_config);
+    let issue = match find_issue(&owner, &name, number, user_config).await {
+        Some(issue) => issue,
+        None => return,
+    };
+
+    let body = body.or_else(|| command_line::compose(format!("Comment on #{} '{}'", number, issue.title).as_str())).unwrap_or_default();
+    if body.trim().is_empty() {
+        println!("Empty comment, nothing was posted");
+        return;
+    }
+    match forge::comment_issue(user_config, &owner, &name, number, &body).await {
+        Ok(()) => println!("{} on #{} '{}'", "Commented".green().bold(), number, issue.title),
+        Err(error) => command_line::throw_error(format!("Failed to comment on #{}: {}", number, error).as_str()),
+    }
+}
+
+pub async fn close(repoPath: &str, number: u64, user_config: &Config) {
+    let (owner, name) = full_name(repoPath, user_config);
+    let issue = match find_issue(&owner, &name, number, user_config).await {
+        Some(issue) => issue,
+        None => return,
+    };
+    if !issue.open {
+        println!("#{} '{}' is already closed", number, issue.title);
+        return;
+    }
+
+    match forge::close_issue(user_config, &owner, &name, number).await {
+        Ok(()) => println!("{} #{} '{}'", "Closed".green().bold(), number, issue.title),
+        Err(error) => command_line::throw_error(format!("Failed to close #{}: {}", number, error).as_str()),
+    }
+}
+
+/// The repository on the forge the origin of the local clone points to.
+fn full_name(repoPath: &str, user_config: &Config) -> (String, String) {
+    let repoName = Path::new(repoPath).file_name().and_then(|name| name.to_str()).unwrap_or(repoPath);
+    remote_full_name(Some(repoPath), repoName, user_config)
+}
+
+async fn find_issue(owner: &str, name: &str, number: u64, user_config: &Config) -> Option<Issue> {
+    match forge::get_issue(user_config, owner, name, number).await {
+        Ok(Some(issue)) => Some(issue),
+        Ok(None) => {
+            command_line::throw_error(format!("Issue #{} not found in {}/{}", number, owner, name).as_str());
+            None
+        }
+        Err(error) => {
+            command_line::throw_error(format!("Failed to get issue #{}: {}", number, error).as_str());
+            None
+        }
+    }
+}
+
+fn state_label(issue: &Issue) -> ColoredString {
+    if issue.open { "open".green() } else { "closed".red() }
+}
+
+// 2024-05-01T12:00:00Z -> 2024-05-01
+fn date(timestamp: &str) -> &str {
+    timestamp.split('T').next().unwrap_or(timestamp)
+}
+
+/// Reads `--state open|closed|all`, `--assignee <user>` (me is you) and `--label <name>` (repeatable).
+pub fn get_issue_filter(args: &[&str], rawArgs: &[&str], user_config: &Config) -> Result<IssueFilter, String> {
+    let state = match get_flag_values(args, "--state").pop().as_deref() {
+        None | Some("open") => IssueState::Open,
+        Some("closed") => IssueState::Closed,
+        Some("all") => IssueState::All,
+        Some(other) => return Err(format!("State '{}' is invalid (open/closed/all)", other)),
+    };
+    let assignee = get_flag_values(rawArgs, "--assignee").pop()
+        .map(|assignee| if assignee.eq_ignore_ascii_case("me") { user_config.username.clone() } else { assignee });
+
+    Ok(IssueFilter {
+        state,
+        assignee,
+        labels: get_flag_values(rawArgs, "--label"),
+    })
+}
+
+/// Splits `<title> (--body <text>)` into the title and the inline body.
+pub fn get_title_and_body(rawArgs: &[&str], start: usize) -> (String, Option<String>) {
+    let words = rawArgs[start..].iter().filter(|word| word.trim() != "").copied().collect::<Vec<&str>>();
+    match words.iter().position(|word| word.eq_ignore_ascii_case("--body")) {
+        Some(position) => (unquote(&words[..position]), Some(unquote(&words[position + 1..]))),
+        None => (unquote(&words), None),
+    }
+}
+
+fn unquote(words: &[&str]) -> String {
+    words.join(" ").trim_matches(|character| character == '"' || character == '\'').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        serde_json::from_str(r#"{"api_key": "", "username": "octocat", "project_path": ""}"#).unwrap()
+    }
+
+    #[test]
+    fn get_title_and_body_splits_at_body() {
+        let args = ["issue", "create", "\"Crash", "on", "start\"", "--body", "It", "crashes", " ", " "];
+        assert_eq!(get_title_and_body(&args, 2), ("Crash on start".to_string(), Some("It crashes".to_string())));
+        let args = ["issue", "create", "'Crash'", " ", " "];
+        assert_eq!(get_title_and_body(&args, 2), ("Crash".to_string(), None));
+    }
+
+    #[test]
+    fn get_title_and_body_without_body_text() {
+        let args = ["issue", "create", "Crash", "--body", " ", " "];
+        assert_eq!(get_title_and_body(&args, 2), ("Crash".to_string(), Some(String::new())));
+        let args = ["issue", "create", "--BODY", "only", "a", "body"];
+        assert_eq!(get_title_and_body(&args, 2), (String::new(), Some("only a body".to_string())));
+    }
+
+    #[test]
+    fn get_issue_filter_reads_state_assignee_and_labels() {
+        let raw = ["issue", "list", "--state", "closed", "--assignee", "me", "--label", "bug", "--label", "\"good", "first", "issue\"", " "];
+        let args = raw.iter().map(|arg| arg.to_lowercase()).collect::<Vec<String>>();
+        let args = args.iter().map(|arg| arg.as_str()).collect::<Vec<&str>>();
+        let filter = get_issue_filter(&args, &raw, &config()).unwrap();
+        assert!(filter.state == IssueState::Closed);
+        // me is the configured user
+        assert_eq!(filter.assignee.as_deref(), Some("octocat"));
+        assert_eq!(filter.labels, vec!["bug".to_string(), "good first issue".to_string()]);
+    }
+
+    #[test]
+    fn get_issue_filter_defaults_and_errors() {
+        let args = ["issue", "list", " ", " "];
+        let filter = get_issue_filter(&args, &args, &config()).unwrap();
+        assert!(filter.state == IssueState::Open);
+        assert_eq!(filter.assignee, None);
+        assert!(filter.labels.is_empty());
+
+        let args = ["issue", "list", "--assignee", "Hubot"];
+        assert_eq!(get_issue_filter(&args, &args, &config()).unwrap().assignee.as_deref(), Some("Hubot"));
+
+        let args = ["issue", "list", "--state", "draft"];
+        assert!(get_issue_filter(&args, &args, &config()).is_err());
+    }
+}
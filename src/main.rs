@@ -5,16 +5,29 @@ use std::env;
 use std::io::{self, Write}; 
 use std::fs::{self, File, OpenOptions}; 
 use std::path::{Path, PathBuf, MAIN_SEPARATOR};
-use std::process::{exit, Command};
+use std::process::{exit, Command, Output};
 use colored::*; 
 use command_line::{check_name, throw_error};
 use git_actions::*;
 use serde::{Serialize, Deserialize};
 use reqwest::header;
 
+mod api;
+mod bulk;
 mod config_manager;
+mod credentials;
 mod command_line;
+mod forge;
+mod forks;
 mod git_actions;
+mod gitea;
+mod github;
+mod gitlab;
+mod issues;
+mod pull_requests;
+mod releases;
+mod repo_settings;
+mod trash;
 #[derive(PartialEq)]
 pub enum State {
     Home,
@@ -31,7 +44,21 @@ pub struct Repository {
 
 #[derive(Deserialize)]
 struct CloneData {
-    clone_url: String
+    clone_url: String,
+    ssh_url: String,
+    name: String,
+    #[serde(default)]
+    private: bool,
+    #[serde(default)]
+    fork: bool,
+    #[serde(default)]
+    archived: bool,
+    language: Option<String>,
+    #[serde(default)]
+    stargazers_count: u32,
+    pushed_at: Option<String>,
+    #[serde(default)]
+    topics: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -39,6 +66,34 @@ struct Config {
     api_key: String,
     username: String,
     project_path: String,
+    // GitHub Enterprise: https://<host>/api/v3 and https://<host>
+    #[serde(default = "config_manager::default_api_url")]
+    api_url: String,
+    #[serde(default = "config_manager::default_web_url")]
+    web_url: String,
+    // how many repositories 'download all' clones at the same time
+    #[serde(default = "config_manager::default_clone_jobs")]
+    clone_jobs: usize,
+    // "https" or "ssh"
+    #[serde(default = "config_manager::default_clone_protocol")]
+    clone_protocol: String,
+    // "github", "gitlab", "gitea" or "forgejo"
+    // GitLab: https://<host>/api/v4, Gitea/Forgejo: https://<host>/api/v1
+    #[serde(default = "config_manager::default_forge")]
+    forge: String,
+    // saved logins, 'account use <name>' copies one into the fields above
+    #[serde(default)]
+    accounts: Vec<Account>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct Account {
+    name: String,
+    forge: String,
+    username: String,
+    api_key: String,
+    api_url: String,
+    web_url: String,
 }
 
 fn avoid_index_error(args: &mut Vec<&str>){
@@ -57,13 +112,13 @@ fn ensure_path_exists<P: AsRef<Path>>(path: P) -> io::Result<()> {
 }
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {    
-    exit(1);
     println!("Searching your project directory for git repositories, this may take a while depending on your project directory size...");
 
     let mut currentState = State::Home;
 
     // init config
     let mut user_config = config_manager::manage_config();
+    credentials::set_from_config(&user_config);
 
 
     // getting all of the repos
@@ -91,33 +146,102 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         // command loop
         match arguements[0] {
             "set" => {
-                    let arg = arguements[1];
-                    let change = rawArgs[2];
-                    if command_line::check_if_empty_and_print_info(&arg.to_string(), "set username,set key,set path"){
-                        if command_line::check_name(&change.to_string(), "Empty change is not valid"){
-
-                            match arg {
-                                "username" => {
-                                    user_config.username = change.to_string();
+                let arg = arguements[1];
+                let change = rawArgs[2];
+                if command_line::check_if_empty_and_print_info(arg, "set username,set key,set path,set forge,set api_url,set web_url,set jobs,set protocol")
+                    && command_line::check_name(change, "Empty change is not valid"){
+
+                    match arg {
+                        "username" => {
+                            user_config.username = change.to_string();
+                            config_manager::write_to_json("./config.json", &user_config);
+                            credentials::set_from_config(&user_config);
+                        }
+                        "key" => {
+                            user_config.api_key = change.to_string();
+                            config_manager::write_to_json("./config.json", &user_config);
+                            credentials::set_from_config(&user_config);
+                        }
+                        "path" => {
+                            user_config.project_path = change.to_string();
+                            config_manager::write_to_json("./config.json", &user_config);
+                        }
+                        "forge" => {
+                            match forge::ForgeKind::parse(change) {
+                                Some(kind) => {
+                                    user_config.forge = change.to_lowercase();
                                     config_manager::write_to_json("./config.json", &user_config);
+                                    println!("Using {}, check that api_url and web_url point to it", kind.name());
                                 }
-                                "key" => {
-                                    user_config.api_key = change.to_string();
+                                None => command_line::throw_error(format!("Forge '{}' is invalid ({})", change, forge::FORGE_NAMES).as_str()),
+                            }
+                        }
+                        "api_url" => {
+                            user_config.api_url = change.trim_end_matches('/').to_string();
+                            config_manager::write_to_json("./config.json", &user_config);
+                        }
+                        "web_url" => {
+                            user_config.web_url = change.trim_end_matches('/').to_string();
+                            config_manager::write_to_json("./config.json", &user_config);
+                            credentials::set_from_config(&user_config);
+                        }
+                        "protocol" => {
+                            match change.to_lowercase().as_str() {
+                                "https" | "ssh" => {
+                                    user_config.clone_protocol = change.to_lowercase();
                                     config_manager::write_to_json("./config.json", &user_config);
+                                    // the clone urls of the local repos depend on it
+                                    git_actions::update_repos(&mut repo_list, &mut repo_names_list, &mut repo_path_list, &user_config);
                                 }
-                                "path" => {
-                                    user_config.project_path = change.to_string();
+                                other => command_line::throw_error(format!("Protocol '{}' is invalid (https/ssh)", other).as_str()),
+                            }
+                        }
+                        "jobs" => {
+                            match change.parse::<usize>() {
+                                Ok(jobs) if jobs > 0 => {
+                                    user_config.clone_jobs = jobs;
                                     config_manager::write_to_json("./config.json", &user_config);
                                 }
-                                other => {
-                                    command_line::throw_error(format!("Arguement {} is not valid", other).as_str());
+                                _ => command_line::throw_error(format!("'{}' is not a valid number of jobs", change).as_str()),
+                            }
+                        }
+                        other => {
+                            command_line::throw_error(format!("Arguement {} is not valid", other).as_str());
+                        }
+                    }
+                }
+            }
+
+            "account" => {
+                let name = rawArgs[2];
+                if command_line::check_if_empty_and_print_info(arguements[1], "account list,account save <name>,account use <name>,account remove <name>") {
+                    match arguements[1] {
+                        "list" => config_manager::print_accounts(&user_config),
+                        "save" => {
+                            if command_line::check_name(name, "Account name is missing") {
+                                config_manager::save_account(&mut user_config, name);
+                            }
+                        }
+                        "use" => {
+                            if command_line::check_name(name, "Account name is missing") {
+                                if config_manager::use_account(&mut user_config, name) {
+                                    credentials::set_from_config(&user_config);
+                                    git_actions::update_repos(&mut repo_list, &mut repo_names_list, &mut repo_path_list, &user_config);
+                                    println!("Now using {} as {} on {}", name.blue().bold(), user_config.username, forge::name(&user_config));
+                                }
+                                else {
+                                    command_line::throw_error(format!("Account '{}' not found", name).as_str());
                                 }
                             }
-                        
+                        }
+                        "remove" => {
+                            if command_line::check_name(name, "Account name is missing") && !config_manager::remove_account(&mut user_config, name) {
+                                command_line::throw_error(format!("Account '{}' not found", name).as_str());
+                            }
+                        }
+                        other => command_line::throw_error(format!("Arguement '{}' is not valid", other).as_str()),
                     }
                 }
-            
-                    
             }
 
             "show" => {
@@ -125,6 +249,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     println!("{}: {}", "username".blue().underline(), &user_config.username);
                     println!("{}: {}", "api key".blue().underline(), &user_config.api_key);
                     println!("{}: {}", "Project path".blue().underline(), &user_config.project_path);
+                    println!("{}: {}", "Forge".blue().underline(), forge::name(&user_config));
+                    println!("{}: {}", "API url".blue().underline(), &user_config.api_url);
+                    println!("{}: {}", "Web url".blue().underline(), &user_config.web_url);
+                    println!("{}: {}", "Clone jobs".blue().underline(), &user_config.clone_jobs);
+                    println!("{}: {}", "Clone protocol".blue().underline(), &user_config.clone_protocol);
                 }
 
                 else {
@@ -133,6 +262,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             println!("{}: {}", "username".blue().underline(), &user_config.username);
                             println!("{}: {}", "api key".blue().underline(), &user_config.api_key);
                             println!("{}: {}", "Project path".blue().underline(), &user_config.project_path);
+                            println!("{}: {}", "Forge".blue().underline(), forge::name(&user_config));
+                            println!("{}: {}", "API url".blue().underline(), &user_config.api_url);
+                            println!("{}: {}", "Web url".blue().underline(), &user_config.web_url);
+                            println!("{}: {}", "Clone jobs".blue().underline(), &user_config.clone_jobs);
+                            println!("{}: {}", "Clone protocol".blue().underline(), &user_config.clone_protocol);
                         }
                         else {
                             command_line::throw_error(format!("Arguement '{}' not found", arguements[1]).as_str())
@@ -145,7 +279,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 if !repo_names_list.contains(&rawArgs[1].to_string()){
                     git_actions::update_repos(&mut repo_list, &mut repo_names_list, &mut repo_path_list, &user_config); 
                 }
-                if command_line::check_if_empty_and_print_info(&arguements[1].to_string(), "open config,open <filename>"){
+                if command_line::check_if_empty_and_print_info(arguements[1], "open config,open <filename>"){
 
                     if arguements[1] == "config"{
                         currentState = State::Config;
@@ -175,46 +309,35 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
 
             "upload" => {
-                let mut branch_name = "main".to_string();
+                let force = git_actions::get_force(&arguements);
+                let branch_name = git_actions::get_branch_name(&rawArgs);
                 if arguements[1] == "all" {
                     for repo in &repo_list{
-                        let mut force = false;
-                        for arg in &arguements{
-                            if arg == &"--force"{
-                                force = true;
-                            }
-                        }
-                        git_actions::upload(&repo.Path, &"commited by Git-Manager".to_string(), force, "main".to_string());
+                        git_actions::upload(&repo.Path, "commited by Git-Manager", force, branch_name.clone());
                     }
                 }
 
                 else {
                     
                     // upload a specific file
-                    // upload <file> <commit message> (--force)
-                    // upload das hier ist die 
+                    // upload <file> <commit message> (--branch <name>) (--force)
                     if let State::Repo(ref reponame) = currentState{
-                        let force = git_actions::get_force(&arguements);
                         let commit_msg = git_actions::get_commit_msg(&rawArgs, 1);
-                        if arguements[1] != " "{
-                            branch_name = rawArgs[1].to_string();
+                        match repo_list.iter().find(|repo| &repo.Name == reponame) {
+                            Some(repo) => git_actions::upload(&repo.Path, &commit_msg, force, branch_name),
+                            None => command_line::throw_error(format!("Repository '{}' not found in {}", reponame, &user_config.project_path).as_str()),
                         }
-                        git_actions::upload(&reponame.to_string(), &&commit_msg.to_string(), force, branch_name);
                     }
                     else {
                         let name = rawArgs[1].to_string().clone();
                         if !repo_names_list.contains(&name){
                             git_actions::update_repos(&mut repo_list, &mut repo_names_list, &mut repo_path_list, &user_config);
                         }
-                        if command_line::check_if_empty_and_print_info(&name, "upload all,upload <name> (commit message) (branch name) (--force)"){
+                        if command_line::check_if_empty_and_print_info(&name, "upload all (--branch <name>) (--force),upload <name> (commit message) (--branch <name>) (--force)"){
                             for repo in &repo_list{
-                                if &repo.Name == &name {
-                                    let force = git_actions::get_force(&arguements);
+                                if repo.Name == name {
                                     let commit_msg = git_actions::get_commit_msg(&rawArgs, 2);
-                                    if arguements[2] != " "{
-                                        branch_name = rawArgs[2].to_string();
-                                    }
-                                    git_actions::upload(&repo.Path, &commit_msg.to_string(), force, "main".to_string());
+                                    git_actions::upload(&repo.Path, &commit_msg, force, branch_name.clone());
                                     }
                             
 
@@ -230,26 +353,30 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
 
             "download" => {
-                let mut path = user_config.project_path.clone();
-                if arguements[4] != " "{
-                    path = rawArgs[4].to_string();
-                } 
-                if command_line::check_if_empty_and_print_info(&arguements[1].to_string(), "download all from <user> (to path),download <repo> from <user> (to path)"){
+                // 'to <path>' comes after the user or organization
+                let owner_position = if arguements[1] == "all" && arguements[3] == "org" { 4 } else { 3 };
+                let path = git_actions::get_target_path(&arguements, &rawArgs, owner_position + 1, &user_config.project_path);
+                let options = git_actions::get_download_options(&arguements, &rawArgs, &user_config);
+                if command_line::check_if_empty_and_print_info(arguements[1], "download all from <user> (to path) (--jobs <n>) (--sync),download all from org <org> (to path) (--jobs <n>) (--sync),download <repo> from <user> (to path) (--sync)"){
                     if arguements[1] == "all"{
                         if arguements[2] == "from" {
-                            let username = rawArgs[3];
-                            if command_line::check_name(&username.to_string(), "Username is missing"){
-                                    // download own repos so also privat repos
-                                    if username == &user_config.username {
-                                        // from own acc so also private repos
-                                        let api_key = Some(user_config.api_key.as_str());
-                                        git_actions::clone_all_repos(username, api_key, &path).await?;
+                            if arguements[3] == "org" {
+                                // organizations always need the token, otherwise private repos are missing
+                                let org = rawArgs[4];
+                                if command_line::check_name(org, "Organization name is missing"){
+                                    if let Err(error) = git_actions::clone_all_org_repos(org, &path, &options, &user_config).await {
+                                        command_line::throw_error(format!("Failed to download the repositories of '{}': {}", org, error).as_str());
+                                    }
+                                }
+                            }
+                            else {
+                                let username = rawArgs[3];
+                                if command_line::check_name(username, "Username is missing"){
+                                    // for your own account private repos are included
+                                    if let Err(error) = git_actions::clone_all_repos(username, &path, &options, &user_config).await {
+                                        command_line::throw_error(format!("Failed to download the repositories of '{}': {}", username, error).as_str());
                                     }
-                                    else {
-                                        let api_key = None;
-                                        git_actions::clone_all_repos(username, api_key, &path).await?;
-                                    }  
-                                
+                                }
                             }
                         }
                         else {
@@ -259,11 +386,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     else {
                         let repoName = rawArgs[1];
                         
-                        if command_line::check_name(&repoName.to_string(), "Repository name is missing"){
+                        if command_line::check_name(repoName, "Repository name is missing"){
                             if arguements[2] == "from" {
                                 let username = rawArgs[3];
-                                if command_line::check_name(&username.to_string(), "Username is missing"){
-                                    git_actions::download(&repoName.to_string(), &username.to_string(), &path)
+                                if command_line::check_name(username, "Username is missing"){
+                                    git_actions::download(repoName, username, &path, &options, &user_config).await
                                 }
                             }
                             else{
@@ -274,25 +401,62 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
             }
 
+            "fork" => {
+                let path = git_actions::get_target_path(&arguements, &rawArgs, 4, &user_config.project_path);
+                if command_line::check_if_empty_and_print_info(arguements[1], "fork <repo> from <user> (to path)") {
+                    let repoName = rawArgs[1];
+                    if arguements[2] == "from" {
+                        let username = rawArgs[3];
+                        if command_line::check_name(username, "Username is missing")
+                            && forks::fork(repoName, username, &path, git_actions::use_ssh(&arguements, &user_config), &user_config).await {
+                            git_actions::update_repos(&mut repo_list, &mut repo_names_list, &mut repo_path_list, &user_config);
+                        }
+                    }
+                    else {
+                        command_line::throw_error("Keyword 'from' is missing: fork <repo> from <user>");
+                    }
+                }
+            }
+            "sync" => {
+                if let State::Repo(ref repoName) = currentState {
+                    if command_line::check_if_empty_and_print_info(arguements[1], "sync fork") {
+                        match repo_list.iter().find(|repo| &repo.Name == repoName) {
+                            Some(repo) => {
+                                match arguements[1] {
+                                    "fork" => forks::sync(&repo.Path, &user_config).await,
+                                    other => command_line::throw_error(format!("Arguement '{}' is not valid", other).as_str()),
+                                }
+                            }
+                            None => command_line::throw_error(format!("Repository '{}' not found in {}", repoName, &user_config.project_path).as_str()),
+                        }
+                    }
+                }
+                else {
+                    command_line::throw_error("Open a repository first: open <repo name>");
+                }
+            }
+
             "update" => {
+                let force = git_actions::get_force(&arguements);
+                let branch_name = git_actions::get_branch_name(&rawArgs);
+                let strategy = match git_actions::get_update_strategy(&arguements) {
+                    Ok(strategy) => strategy,
+                    Err(error) => {
+                        command_line::throw_error(error.as_str());
+                        continue;
+                    }
+                };
                 if arguements[1] == "all" {
                     for repo in &repo_list {
-                        let force = git_actions::get_force(&arguements);
-                        git_actions::update(&repo.Path, force, "main".to_string());
+                        git_actions::update(&repo.Path, force, branch_name.clone(), strategy);
                     }
                 }
                 else {
                     // update a specific file
                     if let State::Repo(ref reponame) = currentState{
-                        let force = git_actions::get_force(&arguements);
-                        let path = find_file_in_path(&user_config.project_path, &reponame);
-                        let mut branch_name = "main".to_string();
-                        if arguements[1] != " "{
-                            branch_name = rawArgs[1].to_string();
-                        }
-                        match path{
-                            Ok(path) => git_actions::update(&path, force, branch_name),
-                            Err(error) => println!("error while updating: {:?}", error),
+                        match repo_list.iter().find(|repo| &repo.Name == reponame) {
+                            Some(repo) => git_actions::update(&repo.Path, force, branch_name, strategy),
+                            None => command_line::throw_error(format!("Repository '{}' not found in {}", reponame, &user_config.project_path).as_str()),
                         }
                     }
                     else {
@@ -300,22 +464,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         if !repo_names_list.contains(&name){
                             git_actions::update_repos(&mut repo_list, &mut repo_names_list, &mut repo_path_list, &user_config);
                         }
-                        if command_line::check_if_empty_and_print_info(&name, "update all (--force),update <name> (--force) (branch name)"){
-                            if repo_names_list.contains(&name) {
-                                let force = git_actions::get_force(&arguements);
-                                
-                                let path = find_file_in_path(&user_config.project_path, &name);
-                                let mut branch_name = "main".to_string();
-                                if arguements[1] != " "{
-                                    branch_name = rawArgs[1].to_string();
-                                }
-                                match path{
-                                    Ok(path) => git_actions::update(&path, force, branch_name),
-                                    Err(error) => println!("error while updating: {:?}", error),
-                                }
-                            }
-                            else {
-                                command_line::throw_error("File not found");
+                        if command_line::check_if_empty_and_print_info(&name, "update all (--rebase / --merge) (--force),update <name> (--branch <name>) (--rebase / --merge) (--force)"){
+                            match repo_list.iter().find(|repo| repo.Name == name) {
+                                Some(repo) => git_actions::update(&repo.Path, force, branch_name, strategy),
+                                None => command_line::throw_error("File not found"),
                             }
                         }
                    }
@@ -326,18 +478,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 let secondArg = arguements[1];
                 let mut message ;
                 if let State::Repo(ref _reponame) = currentState{
-                    message = "delete repo,delete branch <name>"
+                    message = "delete repo,delete branch <name> (--local / --remote)"
                 }
                 else {
-                    message = "delete repo <name>,delete branch <name> from <repo>,delete folder <name>"
+                    message = "delete repo <name>,delete branch <name> from <repo> (--local / --remote),delete folder <name>"
                 }
                 
-                if command_line::check_if_empty_and_print_info(&secondArg.to_string(), &message){
+                if command_line::check_if_empty_and_print_info(secondArg, message){
                     match secondArg{
                         "folder" => {
                             let name = rawArgs[2];
-                            let path = git_actions::find_file_in_path(&user_config.project_path, &name);
-                            if command_line::check_name(&name.to_string(), "Folder name is missing"){
+                            let path = git_actions::find_file_in_path(&user_config.project_path, name);
+                            if command_line::check_name(name, "Folder name is missing"){
                                 // delete folder <name>
                                 match path {
                                     Ok(filePath) => {
@@ -352,44 +504,43 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         }
                         "repo" => {
                             if let State::Repo(ref reponame) = currentState{
-                                git_actions::delete_repo(&reponame, &user_config.api_key);
-                                currentState = State::Home; // move back to home since the repo is deleted
+                                let localPath = repo_list.iter().find(|repo| &repo.Name == reponame).map(|repo| repo.Path.as_str());
+                                if git_actions::delete_repo(reponame, localPath, &user_config).await {
+                                    currentState = State::Home; // move back to home since the repo is deleted
+                                }
                             }
                             else {
-                                // chekcne ob es das repo gibt
                                 let name = rawArgs[2];
-                                if command_line::check_name(&name.to_string(), "Repository name is missing"){
-                                    if repo_names_list.contains(&name.to_string()) {
-                                        git_actions::delete_repo(&name.to_string(), &user_config.api_key);
-                                    }
-                                    else {
-                                        command_line::throw_error("Repository not found")
-                                    }
+                                if command_line::check_name(name, "Repository name is missing"){
+                                    // the repo doesn't have to be cloned to be deleted
+                                    let localPath = repo_list.iter().find(|repo| repo.Name == name).map(|repo| repo.Path.as_str());
+                                    git_actions::delete_repo(name, localPath, &user_config).await;
                                 }
-
-
                             }                
                         }
                         "branch" => {
-                            if let State::Repo(ref reponame) = currentState{
-                                let branchName = rawArgs[2];
-                                git_actions::delete_branch(&reponame,&branchName.to_string(),&user_config.api_key);
-                            }
-                            else{
-                                let branchName = rawArgs[2];
-                                if command_line::check_name(&branchName.to_string(), "Branch name is missing"){
-                                    if arguements[3] == "from" {
-                                        let repoName = rawArgs[4];
-                                        if command_line::check_name(&repoName.to_string(), "Repository name is missing"){
-
-                                        git_actions::delete_branch(&repoName.to_string(), &branchName.to_string(), &user_config.api_key);
-                                        }
-                                    }
-                                    else {
-                                        command_line::throw_error("Keyword 'from' is missing: delete branch <name> from <repo>")
+                            let branchName = rawArgs[2];
+                            let (local, remote) = git_actions::get_branch_targets(&arguements);
+                            if command_line::check_name(branchName, "Branch name is missing"){
+                                // delete branch <name> in a repo, delete branch <name> from <repo> at home
+                                let repoName = if let State::Repo(ref reponame) = currentState {
+                                    Some(reponame.as_str())
+                                }
+                                else if arguements[3] == "from" {
+                                    Some(rawArgs[4]).filter(|name| command_line::check_name(name, "Repository name is missing"))
+                                }
+                                else {
+                                    command_line::throw_error("Keyword 'from' is missing: delete branch <name> from <repo>");
+                                    None
+                                };
+
+                                if let Some(repoName) = repoName {
+                                    match repo_list.iter().find(|repo| repo.Name == repoName) {
+                                        Some(repo) => git_actions::delete_branch(&repo.Path, branchName, local, remote, &user_config).await,
+                                        None => command_line::throw_error(format!("Repository '{}' not found in {}", repoName, &user_config.project_path).as_str()),
                                     }
                                 }
-                            } 
+                            }
                         }
                         other => command_line::throw_error(format!("Arguement '{}' is not valid", secondArg).as_str())
                         
@@ -407,24 +558,37 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 let name = rawArgs[2].to_string();
                 let mut message = "";
                 if let State::Repo(ref reponame) = currentState{
-                    message = "create branch <name>"
+                    message = "create branch <name> (from <base>)"
                 }
                 else {
-                    message = "create repo <name> (to path),create branch <name> in <repo>"
+                    message = "create repo <name> <public / private> (path),create branch <name> in <repo> (from <base>)"
                 }
                 
-                if command_line::check_if_empty_and_print_info(&arguements[1].to_string(), &message){
+                if command_line::check_if_empty_and_print_info(arguements[1], message){
 
                     if arguements[1] == "branch"{
                         if command_line::check_name(&name, "Branch name is missing"){
                             if let State::Repo(ref repoName) = currentState {
-                                git_actions::create_branch(&repoName, &name, &user_config.api_key);
+                                // create branch <name> (from <base>)
+                                let base = if arguements[3] == "from" { Some(rawArgs[4]) } else { None };
+                                if base.is_none_or(|base| command_line::check_name(base, "Base branch is missing")) {
+                                    match repo_list.iter().find(|repo| &repo.Name == repoName) {
+                                        Some(repo) => git_actions::create_branch(&repo.Path, &name, base, &user_config).await,
+                                        None => command_line::throw_error(format!("Repository '{}' not found in {}", repoName, &user_config.project_path).as_str()),
+                                    }
+                                }
                             }
                             else {
+                                // create branch <name> in <repo> (from <base>)
                                 if arguements[3] == "in"{
                                     let repoName = rawArgs[4];
-                                    if command_line::check_name(&repoName.to_string(), "Repository name is missing"){
-                                        git_actions::create_branch(&repoName.to_string(), &name, &user_config.api_key);
+                                    let base = if arguements[5] == "from" { Some(rawArgs[6]) } else { None };
+                                    if command_line::check_name(repoName, "Repository name is missing")
+                                        && base.is_none_or(|base| command_line::check_name(base, "Base branch is missing")) {
+                                        match repo_list.iter().find(|repo| repo.Name == repoName) {
+                                            Some(repo) => git_actions::create_branch(&repo.Path, &name, base, &user_config).await,
+                                            None => command_line::throw_error(format!("Repository '{}' not found in {}", repoName, &user_config.project_path).as_str()),
+                                        }
                                     }
                                 }
 
@@ -449,26 +613,31 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                                 panic!("Error while creating path");
                             }
 
-                            if repo_names_list.contains(&name.to_string()){
-                                throw_error(format!("Repository '{}' already exists", name).as_str());
-                            }
-                            else {
-                                let mut privacy = true;
-                                if check_name(&privacystr.to_string(), "Privacy is missing (public, private)"){
-                                    match privacystr {
-                                        "public" => {
-                                            create_repo(&name.to_string(), &true, &path.to_string(), &user_config.api_key)
-                                        } 
-                                        "private" => {
-                                            create_repo(&name.to_string(), &false, &path.to_string(), &user_config.api_key)
-                                        }
-                                        other => {
-                                            command_line::throw_error(format!("Privacy arguement '{}' is invalid (public/private)", other).as_str());
-                                        }
-                                    }
-
+                            let public = match privacystr {
+                                "public" => Some(true),
+                                "private" => Some(false),
+                                " " => {
+                                    command_line::throw_error("Privacy is missing (public, private)");
+                                    None
+                                }
+                                other => {
+                                    command_line::throw_error(format!("Privacy arguement '{}' is invalid (public/private)", other).as_str());
+                                    None
                                 }
+                            };
 
+                            if let Some(public) = public {
+                                if repo_names_list.contains(&name.to_string()){
+                                    throw_error(format!("Repository '{}' already exists", name).as_str());
+                                }
+                                else {
+                                    // the name might also be taken on the forge without being cloned here
+                                    match forge::repository_exists(&user_config, &user_config.username, name).await {
+                                        Ok(true) => throw_error(format!("Repository '{}' already exists on your {} account", name, forge::name(&user_config)).as_str()),
+                                        Ok(false) => create_repo(name, &public, &path, &user_config).await,
+                                        Err(error) => throw_error(format!("Could not check if '{}' already exists: {}", name, error).as_str()),
+                                    }
+                                }
                             }
 
                         }       
@@ -492,13 +661,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         Ok(file_path) => {
                             if !repo_names_list.contains(&name) {
                                 let privacy = arguements[2];
-                                if command_line::check_name(&privacy.to_string(), "Privacy arguement is missing: migrate <project> <public/private>"){
+                                if command_line::check_name(privacy, "Privacy arguement is missing: migrate <project> <public/private>"){
                                     match privacy {
                                         "public" => {
-                                            migrate(&file_path, &name, true, &user_config.api_key);
+                                            migrate(&file_path, &name, true, &user_config).await;
                                         } 
                                         "private" => {
-                                            migrate(&file_path, &name, false, &user_config.api_key);
+                                            migrate(&file_path, &name, false, &user_config).await;
                                         }
                                         other => {
                                             command_line::throw_error(format!("Privacy arguement '{}' is invalid (public/private)", privacy).as_str());
@@ -525,13 +694,182 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
                 // update repo list, names, and path in case a repo got added or deleted
                 git_actions::update_repos(&mut repo_list, &mut repo_names_list, &mut repo_path_list, &user_config);
-                if repo_path_list.len() == 0 {
+                if arguements[1] == "remote" {
+                    // list remote (user)
+                    let username = if arguements[2] != " " { rawArgs[2] } else { user_config.username.as_str() };
+                    git_actions::list_remote_repos(username, &repo_list, &user_config).await;
+                }
+                else if repo_path_list.is_empty() {
                     command_line::throw_error("No git projects found")
                 }
                 else {
                     git_actions::print_repo_list(&repo_path_list);
                 }
             }
+            "pr" => {
+                if let State::Repo(ref repoName) = currentState {
+                    if command_line::check_if_empty_and_print_info(arguements[1], "pr create (title) (--base <branch>),pr list,pr checkout <number>,pr merge <number> (--squash / --rebase / --merge)") {
+                        match repo_list.iter().find(|repo| &repo.Name == repoName) {
+                            Some(repo) => {
+                                let number = arguements[2].trim_start_matches('#').parse::<u64>();
+                                match arguements[1] {
+                                    "create" => {
+                                        let title = pull_requests::get_title(&rawArgs, 2);
+                                        let base = git_actions::get_flag_values(&rawArgs, "--base").pop();
+                                        pull_requests::create(&repo.Path, title, base.as_deref(), &user_config).await;
+                                    }
+                                    "list" => pull_requests::list(&repo.Path, &user_config).await,
+                                    "checkout" => match number {
+                                        Ok(number) => pull_requests::checkout(&repo.Path, number, &user_config).await,
+                                        Err(_) => command_line::throw_error("Pull request number is missing: pr checkout <number>"),
+                                    },
+                                    "merge" => match number {
+                                        Ok(number) => pull_requests::merge(&repo.Path, number, pull_requests::get_merge_method(&arguements), &user_config).await,
+                                        Err(_) => command_line::throw_error("Pull request number is missing: pr merge <number>"),
+                                    },
+                                    other => command_line::throw_error(format!("Arguement '{}' is not valid", other).as_str()),
+                                }
+                            }
+                            None => command_line::throw_error(format!("Repository '{}' not found in {}", repoName, &user_config.project_path).as_str()),
+                        }
+                    }
+                }
+                else {
+                    command_line::throw_error("Open a repository first: open <repo name>");
+                }
+            }
+            "issue" => {
+                if let State::Repo(ref repoName) = currentState {
+                    if command_line::check_if_empty_and_print_info(arguements[1], "issue list (--state open/closed/all) (--assignee me) (--label <name>),issue show <number>,issue create <title> (--body <text>),issue comment <number> (text),issue close <number>") {
+                        match repo_list.iter().find(|repo| &repo.Name == repoName) {
+                            Some(repo) => {
+                                let number = arguements[2].trim_start_matches('#').parse::<u64>();
+                                match (arguements[1], number) {
+                                    ("list", _) => match issues::get_issue_filter(&arguements, &rawArgs, &user_config) {
+                                        Ok(filter) => issues::list(&repo.Path, &filter, &user_config).await,
+                                        Err(error) => command_line::throw_error(&error),
+                                    },
+                                    ("create", _) => {
+                                        let (title, body) = issues::get_title_and_body(&rawArgs, 2);
+                                        if command_line::check_name(&title, "Issue title is missing: issue create <title>") {
+                                            issues::create(&repo.Path, &title, body, &user_config).await;
+                                        }
+                                    }
+                                    ("show", Ok(number)) => issues::show(&repo.Path, number, &user_config).await,
+                                    ("comment", Ok(number)) => {
+                                        let text = rawArgs[3..].iter().filter(|word| word.trim() != "").copied().collect::<Vec<&str>>().join(" ");
+                                        let text = if text.is_empty() { None } else { Some(text) };
+                                        issues::comment(&repo.Path, number, text, &user_config).await;
+                                    }
+                                    ("close", Ok(number)) => issues::close(&repo.Path, number, &user_config).await,
+                                    ("show" | "comment" | "close", Err(_)) => command_line::throw_error(format!("Issue number is missing: issue {} <number>", arguements[1]).as_str()),
+                                    (other, _) => command_line::throw_error(format!("Arguement '{}' is not valid", other).as_str()),
+                                }
+                            }
+                            None => command_line::throw_error(format!("Repository '{}' not found in {}", repoName, &user_config.project_path).as_str()),
+                        }
+                    }
+                }
+                else {
+                    command_line::throw_error("Open a repository first: open <repo name>");
+                }
+            }
+            "release" => {
+                if let State::Repo(ref repoName) = currentState {
+                    if command_line::check_if_empty_and_print_info(arguements[1], "release create <tag> (--asset <file>),release list,release delete <tag>") {
+                        match repo_list.iter().find(|repo| &repo.Name == repoName) {
+                            Some(repo) => {
+                                let tag = rawArgs[2];
+                                match arguements[1] {
+                                    "create" => {
+                                        if command_line::check_name(tag, "Tag is missing: release create <tag>") {
+                                            let assets = git_actions::get_flag_values(&rawArgs, "--asset");
+                                            releases::create(&repo.Path, tag, &assets, &user_config).await;
+                                        }
+                                    }
+                                    "list" => releases::list(&repo.Path, &user_config).await,
+                                    "delete" => {
+                                        if command_line::check_name(tag, "Tag is missing: release delete <tag>") {
+                                            releases::delete(&repo.Path, tag, &user_config).await;
+                                        }
+                                    }
+                                    other => command_line::throw_error(format!("Arguement '{}' is not valid", other).as_str()),
+                                }
+                            }
+                            None => command_line::throw_error(format!("Repository '{}' not found in {}", repoName, &user_config.project_path).as_str()),
+                        }
+                    }
+                }
+                else {
+                    command_line::throw_error("Open a repository first: open <repo name>");
+                }
+            }
+            "repo" => {
+                let mut renamed_to = None;
+                if let State::Repo(ref repoName) = currentState {
+                    if command_line::check_if_empty_and_print_info(arguements[1], "repo set visibility <public / private>,repo set description <text>,repo set topics <a,b,c>,repo rename <new name>,repo archive,repo unarchive") {
+                        match repo_list.iter().find(|repo| &repo.Name == repoName) {
+                            Some(repo) => {
+                                match arguements[1] {
+                                    "set" => {
+                                        let value = rawArgs[3..].iter().filter(|word| word.trim() != "").copied().collect::<Vec<&str>>().join(" ");
+                                        let value = value.trim_matches(|character| character == '"' || character == '\'');
+                                        if command_line::check_name(arguements[2], "Setting is missing: repo set <visibility / description / topics> <value>")
+                                            && command_line::check_name(value, "Value is missing: repo set <setting> <value>") {
+                                            repo_settings::set(&repo.Path, arguements[2], value, &user_config).await;
+                                        }
+                                    }
+                                    "rename" => {
+                                        let new_name = rawArgs[2];
+                                        if command_line::check_name(new_name, "New name is missing: repo rename <new name>") {
+                                            renamed_to = repo_settings::rename(&repo.Path, new_name, &user_config).await;
+                                        }
+                                    }
+                                    "archive" => repo_settings::set_archived(&repo.Path, true, &user_config).await,
+                                    "unarchive" => repo_settings::set_archived(&repo.Path, false, &user_config).await,
+                                    other => command_line::throw_error(format!("Arguement '{}' is not valid", other).as_str()),
+                                }
+                            }
+                            None => command_line::throw_error(format!("Repository '{}' not found in {}", repoName, &user_config.project_path).as_str()),
+                        }
+                    }
+                }
+                else {
+                    command_line::throw_error("Open a repository first: open <repo name>");
+                }
+
+                // the folder has a new name, so the repo list and the prompt have to follow
+                if let Some(new_path) = renamed_to {
+                    git_actions::update_repos(&mut repo_list, &mut repo_names_list, &mut repo_path_list, &user_config);
+                    if let Some(new_name) = Path::new(&new_path).file_name().and_then(|name| name.to_str()) {
+                        currentState = State::Repo(new_name.to_string());
+                    }
+                }
+            }
+            "rate" => {
+                if let Err(error) = forge::print_rate_limit(&user_config).await {
+                    command_line::throw_error(format!("Failed to get the rate limit: {}", error).as_str());
+                }
+            }
+            "trash" => {
+                if command_line::check_if_empty_and_print_info(arguements[1], "trash list,trash restore <id>,trash empty"){
+                    match arguements[1] {
+                        "list" => trash::list(),
+                        "restore" => {
+                            if command_line::check_name(arguements[2], "Id is missing: trash restore <id>"){
+                                match arguements[2].parse::<u32>() {
+                                    Ok(id) => trash::restore(id),
+                                    Err(_) => command_line::throw_error(format!("'{}' is not a valid id", arguements[2]).as_str()),
+                                }
+                            }
+                            // the restored folder might be a git repository
+                            git_actions::update_repos(&mut repo_list, &mut repo_names_list, &mut repo_path_list, &user_config);
+                        }
+                        "empty" => trash::empty(),
+                        other => command_line::throw_error(format!("Arguement '{}' is not valid", other).as_str()),
+                    }
+                }
+            }
             "close" => {
                 currentState = State::Home;
             }
@@ -0,0 +1,291 @@
+use crate::*;
+use forge::{Checks, Head, MergeMethod, PullRequest, RemoteRepository};
+
+/// Opens a pull request from the current branch, into the default branch unless `base` is given.
+/// In a fork (an `upstream` remote or a parent on the forge) it goes to upstream, otherwise to origin.
+/// list, checkout and merge work on the same repository.
+/// The title defaults to the subject of the latest commit.
+pub async fn create(repoPath: &str, title: Option<String>, base: Option<&str>, user_config: &Config) {
+    let repoName = Path::new(repoPath).file_name().and_then(|name| name.to_str()).unwrap_or(repoPath);
+
+    let branch = match current_branch(repoPath) {
+        Some(branch) => branch,
+        None => {
+            command_line::throw_error(format!("Could not find out which branch '{}' is on (detached HEAD?)", repoName).as_str());
+            return;
+        }
+    };
+
+    let target = match find_target(repoPath, repoName, user_config).await {
+        Some(target) => target,
+        None => return,
+    };
+    let (owner, name, full_name) = (&target.origin.owner.login, &target.origin.name, &target.origin.full_name);
+    let from_fork = target.is_fork();
+    let target = target.repository();
+
+    let base = base.map(|base| base.to_string()).unwrap_or(target.default_branch.clone());
+    if branch == base && !from_fork {
+        command_line::throw_error(format!("You are on '{}' itself, create a branch for your changes first (create branch <name>)", base).as_str());
+        return;
+    }
+
+    // the forge only knows what was pushed
+    let on_remote = match forge::branch_exists(user_config, owner, name, &branch).await {
+        Ok(exists) => exists,
+        Err(error) => {
+            command_line::throw_error(format!("Failed to check the branches of '{}': {}", full_name, error).as_str());
+            return;
+        }
+    };
+    let unpushed = if on_remote {
+        ahead_behind(repoPath, "HEAD", &format!("origin/{}", branch)).map(|(ahead, _)| ahead).unwrap_or(0)
+    }
+    else {
+        1
+    };
+    if unpushed > 0 {
+        if !command_line::confirm(format!("'{}' has commits that are not pushed yet, push it now?", branch).as_str()) {
+            println!("No pull request was opened");
+            return;
+        }
+        if let Err(error) = run_git_checked(repoPath, &["push", "--set-upstream", "origin", &branch]) {
+            command_line::throw_error(format!("Failed to push '{}': {}", branch, describe_git_error(&error)).as_str());
+            return;
+        }
+    }
+
+    let title = match title {
+        Some(title) => title,
+        None => {
+            let subject = run_git_checked(repoPath, &["log", "-1", "--format=%s"]).unwrap_or_default();
+            let typed = command_line::input(format!("Title (empty for '{}'):", subject).as_str());
+            if typed.is_empty() { subject } else { typed }
+        }
+    };
+    if title.trim().is_empty() {
+        command_line::throw_error("The pull request needs a title");
+        return;
+    }
+    let body = command_line::input("Description (optional):");
+
+    match forge::create_pull_request(user_config, &target.owner.login, &target.name, &Head { repo: full_name, branch: &branch }, &base, &title, &body).await {
+        Ok(pull) if from_fork => println!("{} #{} '{}' in {} ({}:{} → {}): {}", "Opened".green().bold(), pull.number, pull.title, target.full_name, owner, branch, pull.base, pull.url),
+        Ok(pull) => println!("{} #{} '{}' ({} → {}): {}", "Opened".green().bold(), pull.number, pull.title, pull.head, pull.base, pull.url),
+        Err(error) => command_line::throw_error(format!("Failed to open the pull request: {}", error).as_str()),
+    }
+}
+
+/// Prints the open pull requests with the state of their checks.
+pub async fn list(repoPath: &str, user_config: &Config) {
+    let repoName = Path::new(repoPath).file_name().and_then(|name| name.to_str()).unwrap_or(repoPath);
+    let target = match find_target(repoPath, repoName, user_config).await {
+        Some(target) => target,
+        None => return,
+    };
+    let target = target.repository();
+
+    let pulls = match forge::list_pull_requests(user_config, &target.owner.login, &target.name).await {
+        Ok(pulls) => pulls,
+        Err(error) => {
+            command_line::throw_error(format!("Failed to get the pull requests of '{}': {}", target.full_name, error).as_str());
+            return;
+        }
+    };
+    if pulls.is_empty() {
+        println!("No open pull requests in {}", target.full_name);
+        return;
+    }
+
+    let max_title_length = pulls.iter().map(|pull| pull.title.chars().count()).max().unwrap_or(0);
+    for pull in &pulls {
+        println!(
+            "{:<5} {}{}  {}  {} → {}  {}{}",
+            format!("#{}", pull.number).blue().bold(),
+            pull.title,
+            " ".repeat(max_title_length - pull.title.chars().count()),
+            checks_label(pull.checks),
+            pull.head,
+            pull.base,
+            format!("by {}", pull.author).italic(),
+            if pull.draft { " (draft)".yellow().to_string() } else { String::new() },
+        );
+    }
+    println!("{} open pull request(s)", pulls.len());
+}
+
+fn checks_label(checks: Checks) -> ColoredString {
+    match checks {
+        Checks::Passing => "✓ passing".green(),
+        Checks::Failing => "✗ failing".red(),
+        Checks::Pending => "● pending".yellow(),
+        Checks::Missing => "- no checks".normal(),
+    }
+}
+
+/// Fetches the head of pull request `number` into a local branch and switches to it.
+/// The branch is named like the pull request branch, or pr-<number> if that name is taken.
+pub async fn checkout(repoPath: &str, number: u64, user_config: &Config) {
+    let repoName = Path::new(repoPath).file_name().and_then(|name| name.to_str()).unwrap_or(repoPath);
+    if has_uncommitted_changes(repoPath) {
+        command_line::throw_error(format!("'{}' has uncommitted changes, upload or stash them first", repoName).as_str());
+        return;
+    }
+
+    let target = match find_target(repoPath, repoName, user_config).await {
+        Some(target) => target,
+        None => return,
+    };
+    let pull = match find_pull_request(target.repository(), number, user_config).await {
+        Some(pull) => pull,
+        None => return,
+    };
+
+    let pr_branch = format!("pr-{}", number);
+    let head_taken = git_succeeds(repoPath, &["rev-parse", "--verify", "--quiet", &format!("refs/heads/{}", pull.head)]);
+    let branch = if head_taken { pr_branch } else { pull.head.clone() };
+    let pull_ref = forge::pull_request_ref(user_config, number);
+
+    // fetching into the checked out branch isn't allowed, so that one is fast-forwarded instead
+    let result = if current_branch(repoPath).as_deref() == Some(branch.as_str()) {
+        run_git_checked(repoPath, &["fetch", &target.fetch_from, &pull_ref])
+            .and_then(|_| run_git_checked(repoPath, &["merge", "--ff-only", "FETCH_HEAD"]))
+    }
+    else {
+        run_git_checked(repoPath, &["fetch", &target.fetch_from, &format!("{}:refs/heads/{}", pull_ref, branch)])
+            .and_then(|_| run_git_checked(repoPath, &["checkout", &branch]))
+    };
+    if let Err(error) = result {
+        command_line::throw_error(format!("Failed to check out #{}: {}", number, describe_git_error(&error)).as_str());
+        return;
+    }
+    println!("{} #{} '{}' as branch '{}'", "Checked out".green().bold(), number, pull.title, branch);
+}
+
+/// Merges pull request `number` on the forge after asking.
+pub async fn merge(repoPath: &str, number: u64, method: MergeMethod, user_config: &Config) {
+    let repoName = Path::new(repoPath).file_name().and_then(|name| name.to_str()).unwrap_or(repoPath);
+    let target = match find_target(repoPath, repoName, user_config).await {
+        Some(target) => target,
+        None => return,
+    };
+    let target = target.repository();
+
+    let pull = match find_pull_request(target, number, user_config).await {
+        Some(pull) => pull,
+        None => return,
+    };
+    if pull.checks == Checks::Failing || pull.checks == Checks::Pending {
+        println!("{}", format!("The checks of #{} are {}", number, checks_label(pull.checks)).yellow());
+    }
+    if !command_line::confirm(format!("Merge #{} '{}' into {} ({})?", number, pull.title, pull.base, method.name()).as_str()) {
+        println!("#{} was not merged", number);
+        return;
+    }
+
+    match forge::merge_pull_request(user_config, &target.owner.login, &target.name, number, method).await {
+        Ok(()) => println!("{} #{} into {}, run 'update' to get it locally", "Merged".green().bold(), number, pull.base),
+        Err(error) => command_line::throw_error(format!("Failed to merge #{}: {}", number, error).as_str()),
+    }
+}
+
+/// The repository behind `origin` and, for a fork, the one its pull requests go to.
+struct Target {
+    origin: RemoteRepository,
+    upstream: Option<RemoteRepository>,
+    // a remote name, or the url of the parent when there is no upstream remote
+    fetch_from: String,
+}
+
+impl Target {
+    /// The repository pull requests are opened, listed and merged in.
+    fn repository(&self) -> &RemoteRepository {
+        self.upstream.as_ref().unwrap_or(&self.origin)
+    }
+
+    fn is_fork(&self) -> bool {
+        !self.repository().full_name.eq_ignore_ascii_case(&self.origin.full_name)
+    }
+}
+
+/// Upstream is the `upstream` remote if there is one, else the parent of origin on the forge.
+async fn find_target(repoPath: &str, repoName: &str, user_config: &Config) -> Option<Target> {
+    let (owner, name) = remote_full_name(Some(repoPath), repoName, user_config);
+    let mut origin = find_repository(&owner, &name, user_config).await?;
+
+    let upstream_remote = run_git_checked(repoPath, &["remote", "get-url", "upstream"]).ok().and_then(|url| parse_full_name(&url));
+    let (upstream, fetch_from) = match upstream_remote {
+        Some((upstream_owner, upstream_name)) => (Some(find_repository(&upstream_owner, &upstream_name, user_config).await?), "upstream".to_string()),
+        None => match origin.parent.take() {
+            Some(parent) => {
+                let url = parent.remote_url(user_config).to_string();
+                (Some(*parent), url)
+            }
+            None => (None, "origin".to_string()),
+        },
+    };
+    Some(Target { origin, upstream, fetch_from })
+}
+
+async fn find_repository(owner: &str, name: &str, user_config: &Config) -> Option<RemoteRepository> {
+    match forge::get_repository(user_config, owner, name).await {
+        Ok(Some(remote)) => Some(remote),
+        Ok(None) => {
+            command_line::throw_error(format!("Repository '{}/{}' not found on {} (or your token can't see it)", owner, name, forge::name(user_config)).as_str());
+            None
+        }
+        Err(error) => {
+            command_line::throw_error(format!("Failed to look up '{}/{}': {}", owner, name, error).as_str());
+            None
+        }
+    }
+}
+
+async fn find_pull_request(target: &RemoteRepository, number: u64, user_config: &Config) -> Option<PullRequest> {
+    match forge::get_pull_request(user_config, &target.owner.login, &target.name, number).await {
+        Ok(Some(pull)) => Some(pull),
+        Ok(None) => {
+            command_line::throw_error(format!("Pull request #{} not found in {}", number, target.full_name).as_str());
+            None
+        }
+        Err(error) => {
+            command_line::throw_error(format!("Failed to get pull request #{}: {}", number, error).as_str());
+            None
+        }
+    }
+}
+
+/// Reads `--squash` / `--rebase` / `--merge`, a plain merge commit is the default.
+pub fn get_merge_method(args: &[&str]) -> MergeMethod {
+    if args.contains(&"--squash") {
+        MergeMethod::Squash
+    }
+    else if args.contains(&"--rebase") {
+        MergeMethod::Rebase
+    }
+    else {
+        MergeMethod::Merge
+    }
+}
+
+/// The title after `pr create`, without the `--base <branch>` flag. None if there is no title.
+pub fn get_title(rawArgs: &[&str], start: usize) -> Option<String> {
+    let mut words = Vec::new();
+    let mut skip_next = false;
+    for arg in &rawArgs[start..] {
+        if skip_next {
+            skip_next = false;
+            continue;
+        }
+        if arg.eq_ignore_ascii_case("--base") {
+            skip_next = true;
+            continue;
+        }
+        words.push(*arg);
+    }
+    let title = words.join(" ").trim().trim_matches('"').trim().to_string();
+    if title.is_empty() {
+        return None;
+    }
+    Some(title)
+}
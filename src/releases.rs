@@ -0,0 +1,193 @@
+use crate::*;
+
+/// Publishes a release for `tag`. A missing tag is created as an annotated tag on HEAD and pushed,
+/// the notes list the commits since the previous tag and every file in `assets` is uploaded.
+pub async fn create(repoPath: &str, tag: &str, assets: &[String], user_config: &Config) {
+    let repoName = Path::new(repoPath).file_name().and_then(|name| name.to_str()).unwrap_or(repoPath);
+    let (owner, name) = remote_full_name(Some(repoPath), repoName, user_config);
+
+    if !git_succeeds(repoPath, &["check-ref-format", &format!("refs/tags/{}", tag)]) {
+        command_line::throw_error(format!("'{}' is not a valid tag name", tag).as_str());
+        return;
+    }
+    // relative paths are relative to the repository, e.g. target/release/<binary>
+    let asset_paths = assets.iter()
+        .map(|asset| if Path::new(asset).is_absolute() { PathBuf::from(asset) } else { Path::new(repoPath).join(asset) })
+        .collect::<Vec<PathBuf>>();
+    if let Some(missing) = asset_paths.iter().find(|path| !path.is_file()) {
+        command_line::throw_error(format!("Asset '{}' does not exist", missing.display()).as_str());
+        return;
+    }
+
+    match forge::get_release(user_config, &owner, &name, tag).await {
+        Ok(None) => {}
+        Ok(Some(release)) => {
+            command_line::throw_error(format!("There already is a release for '{}': {}", tag, release.url).as_str());
+            return;
+        }
+        Err(error) => {
+            command_line::throw_error(format!("Failed to check the releases of '{}/{}': {}", owner, name, error).as_str());
+            return;
+        }
+    }
+
+    if !push_tag(repoPath, tag) {
+        return;
+    }
+
+    let notes = release_notes(repoPath, tag);
+    println!("{}\n{}", "Release notes:".bold().underline(), notes);
+    let release = match forge::create_release(user_config, &owner, &name, tag, tag, &notes).await {
+        Ok(release) => release,
+        Err(error) => {
+            command_line::throw_error(format!("Failed to create the release: {}", error).as_str());
+            return;
+        }
+    };
+
+    let mut failed = 0;
+    for path in &asset_paths {
+        let file_name = path.file_name().and_then(|file_name| file_name.to_str()).unwrap_or("asset");
+        let result = match fs::read(path) {
+            Ok(content) => forge::upload_asset(user_config, &owner, &name, &release, file_name, content).await,
+            Err(error) => Err(error.into()),
+        };
+        match result {
+            Ok(()) => println!("{} {}", "✓".green().bold(), file_name),
+            Err(error) => {
+                failed += 1;
+                println!("{} {}: {}", "✗".red().bold(), file_name, error.to_string().red());
+            }
+        }
+    }
+
+    println!("{} release {}: {}", "Published".green().bold(), tag, release.url);
+    if failed > 0 {
+        command_line::throw_error(format!("{} asset(s) could not be uploaded", failed).as_str());
+    }
+}
+
+/// Creates the tag if it doesn't exist yet and makes sure origin has it. A tag created here
+/// is removed again if the push fails.
+fn push_tag(repoPath: &str, tag: &str) -> bool {
+    let tag_ref = format!("refs/tags/{}", tag);
+    let created = !git_succeeds(repoPath, &["rev-parse", "--verify", "--quiet", &tag_ref]);
+    if created {
+        if let Err(error) = run_git_checked(repoPath, &["tag", "-a", tag, "-m", &format!("Release {}", tag)]) {
+            command_line::throw_error(format!("Failed to create tag '{}': {}", tag, error).as_str());
+            return false;
+        }
+        println!("{} tag '{}' on {}", "Created".green().bold(), tag, run_git_checked(repoPath, &["rev-parse", "--short", "HEAD"]).unwrap_or_default());
+    }
+
+    let on_remote = run_git_checked(repoPath, &["ls-remote", "--tags", "origin", &tag_ref])
+        .map(|output| !output.is_empty())
+        .unwrap_or(false);
+    if on_remote {
+        return true;
+    }
+    if let Err(error) = run_git_checked(repoPath, &["push", "origin", &tag_ref]) {
+        if created {
+            let _ = run_git(repoPath, &["tag", "-d", tag]);
+        }
+        command_line::throw_error(format!("Failed to push tag '{}': {}", tag, describe_git_error(&error)).as_str());
+        return false;
+    }
+    true
+}
+
+/// Lists the commits between the previous tag and `tag`, merge commits left out.
+fn release_notes(repoPath: &str, tag: &str) -> String {
+    let previous = run_git_checked(repoPath, &["describe", "--tags", "--abbrev=0", &format!("{}^", tag)]).ok();
+    let range = match &previous {
+        Some(previous) => format!("{}..{}", previous, tag),
+        None => tag.to_string(),
+    };
+    let commits = run_git_checked(repoPath, &["log", "--no-merges", "--format=- %s (%h)", &range]).unwrap_or_default();
+    let commits = if commits.is_empty() { "- No changes".to_string() } else { commits };
+
+    match previous {
+        Some(previous) => format!("## Changes since {}\n\n{}", previous, commits),
+        None => format!("## Changes\n\n{}", commits),
+    }
+}
+
+pub async fn list(repoPath: &str, user_config: &Config) {
+    let repoName = Path::new(repoPath).file_name().and_then(|name| name.to_str()).unwrap_or(repoPath);
+    let (owner, name) = remote_full_name(Some(repoPath), repoName, user_config);
+
+    let releases = match forge::list_releases(user_config, &owner, &name).await {
+        Ok(releases) => releases,
+        Err(error) => {
+            command_line::throw_error(format!("Failed to get the releases of '{}/{}': {}", owner, name, error).as_str());
+            return;
+        }
+    };
+    if releases.is_empty() {
+        println!("No releases in {}/{}", owner, name);
+        return;
+    }
+
+    let max_tag_length = releases.iter().map(|release| release.tag.chars().count()).max().unwrap_or(0);
+    for release in &releases {
+        let mut flags = Vec::new();
+        if release.draft {
+            flags.push("draft");
+        }
+        if release.prerelease {
+            flags.push("pre-release");
+        }
+        println!(
+            "{}{}  {}  {}  {} asset(s)  {}",
+            release.tag.blue().bold(),
+            " ".repeat(max_tag_length - release.tag.chars().count()),
+            release.created_at.split('T').next().unwrap_or_default(),
+            release.name,
+            release.assets.len(),
+            flags.join(", ").yellow(),
+        );
+    }
+    println!("{} release(s)", releases.len());
+}
+
+/// Deletes the release of `tag`, and the tag itself locally and on origin if wanted.
+pub async fn delete(repoPath: &str, tag: &str, user_config: &Config) {
+    let repoName = Path::new(repoPath).file_name().and_then(|name| name.to_str()).unwrap_or(repoPath);
+    let (owner, name) = remote_full_name(Some(repoPath), repoName, user_config);
+
+    let release = match forge::get_release(user_config, &owner, &name, tag).await {
+        Ok(Some(release)) => release,
+        Ok(None) => {
+            command_line::throw_error(format!("No release for '{}' in {}/{}", tag, owner, name).as_str());
+            return;
+        }
+        Err(error) => {
+            command_line::throw_error(format!("Failed to get the release '{}': {}", tag, error).as_str());
+            return;
+        }
+    };
+    if !command_line::confirm(format!("Delete the release '{}' with {} asset(s)?", release.name, release.assets.len()).as_str()) {
+        println!("The release was not deleted");
+        return;
+    }
+    if let Err(error) = forge::delete_release(user_config, &owner, &name, &release).await {
+        command_line::throw_error(format!("Failed to delete the release '{}': {}", tag, error).as_str());
+        return;
+    }
+    println!("{} release '{}'", "Deleted".green().bold(), tag);
+
+    if !command_line::confirm(format!("Also delete the tag '{}' locally and on origin?", tag).as_str()) {
+        return;
+    }
+    if let Err(error) = run_git_checked(repoPath, &["push", "origin", "--delete", &format!("refs/tags/{}", tag)]) {
+        command_line::throw_error(format!("Failed to delete tag '{}' on origin: {}", tag, describe_git_error(&error)).as_str());
+        return;
+    }
+    if git_succeeds(repoPath, &["rev-parse", "--verify", "--quiet", &format!("refs/tags/{}", tag)]) {
+        if let Err(error) = run_git_checked(repoPath, &["tag", "-d", tag]) {
+            command_line::throw_error(format!("Failed to delete tag '{}' locally: {}", tag, error).as_str());
+            return;
+        }
+    }
+    println!("{} tag '{}'", "Deleted".green().bold(), tag);
+}
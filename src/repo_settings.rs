@@ -0,0 +1,118 @@
+use crate::*;
+use forge::RepoUpdate;
+
+/// Changes the visibility, description or topics of the opened repository on the forge.
+pub async fn set(repoPath: &str, setting: &str, value: &str, user_config: &Config) {
+    let (owner, name) = full_name(repoPath, user_config);
+    let full_name = format!("{}/{}", owner, name);
+
+    let result = match setting {
+        "visibility" => {
+            let private = match value.to_lowercase().as_str() {
+                "private" => true,
+                "public" => false,
+                other => {
+                    command_line::throw_error(format!("Visibility '{}' is invalid (public/private)", other).as_str());
+                    return;
+                }
+            };
+            if !private && !command_line::confirm(format!("Make '{}' public? Everyone will be able to see its code and history", full_name).as_str()) {
+                println!("'{}' stays private", full_name);
+                return;
+            }
+            let update = RepoUpdate { private: Some(private), ..Default::default() };
+            forge::update_repository(user_config, &owner, &name, &update).await.map(|_| ())
+        }
+        "description" => {
+            let update = RepoUpdate { description: Some(value.to_string()), ..Default::default() };
+            forge::update_repository(user_config, &owner, &name, &update).await.map(|_| ())
+        }
+        "topics" => {
+            // GitHub only accepts lowercase topics
+            let topics = value.split(',')
+                .map(|topic| topic.trim().to_lowercase())
+                .filter(|topic| !topic.is_empty())
+                .collect::<Vec<String>>();
+            forge::set_topics(user_config, &owner, &name, &topics).await
+        }
+        other => {
+            command_line::throw_error(format!("Setting '{}' is invalid (visibility/description/topics)", other).as_str());
+            return;
+        }
+    };
+
+    match result {
+        Ok(()) => println!("{} the {} of '{}' to '{}'", "Changed".green().bold(), setting, full_name, value),
+        Err(error) => command_line::throw_error(format!("Failed to change the {} of '{}': {}", setting, full_name, error).as_str()),
+    }
+}
+
+pub async fn set_archived(repoPath: &str, archived: bool, user_config: &Config) {
+    let (owner, name) = full_name(repoPath, user_config);
+    let full_name = format!("{}/{}", owner, name);
+    if archived && !command_line::confirm(format!("Archive '{}'? It becomes read-only until it's unarchived", full_name).as_str()) {
+        println!("'{}' was not archived", full_name);
+        return;
+    }
+
+    let update = RepoUpdate { archived: Some(archived), ..Default::default() };
+    match forge::update_repository(user_config, &owner, &name, &update).await {
+        Ok(_) if archived => println!("{} '{}'", "Archived".green().bold(), full_name),
+        Ok(_) => println!("{} '{}'", "Unarchived".green().bold(), full_name),
+        Err(error) => command_line::throw_error(format!("Failed to change '{}': {}", full_name, error).as_str()),
+    }
+}
+
+/// Renames the repository on the forge, points origin to the new url and renames the local folder.
+/// Returns the new path of the clone if the repository was renamed.
+pub async fn rename(repoPath: &str, new_name: &str, user_config: &Config) -> Option<String> {
+    let (owner, name) = full_name(repoPath, user_config);
+    let full_name = format!("{}/{}", owner, name);
+
+    let new_path = Path::new(repoPath).with_file_name(new_name);
+    if new_path.exists() {
+        command_line::throw_error(format!("'{}' already exists, the local folder couldn't be renamed", new_path.display()).as_str());
+        return None;
+    }
+    match forge::repository_exists(user_config, &owner, new_name).await {
+        Ok(false) => {}
+        Ok(true) => {
+            command_line::throw_error(format!("'{}/{}' already exists", owner, new_name).as_str());
+            return None;
+        }
+        Err(error) => {
+            command_line::throw_error(format!("Could not check if '{}/{}' already exists: {}", owner, new_name, error).as_str());
+            return None;
+        }
+    }
+
+    let update = RepoUpdate { name: Some(new_name.to_string()), ..Default::default() };
+    let remote = match forge::update_repository(user_config, &owner, &name, &update).await {
+        Ok(remote) => remote,
+        Err(error) => {
+            command_line::throw_error(format!("Failed to rename '{}': {}", full_name, error).as_str());
+            return None;
+        }
+    };
+    println!("{} '{}' to '{}'", "Renamed".green().bold(), full_name, remote.full_name);
+
+    // keep the protocol the clone already uses
+    let origin = run_git_checked(repoPath, &["remote", "get-url", "origin"]).unwrap_or_default();
+    let uses_ssh = origin.starts_with("git@") || origin.starts_with("ssh://");
+    let new_url = if uses_ssh { &remote.ssh_url } else { &remote.clone_url };
+    if let Err(error) = run_git_checked(repoPath, &["remote", "set-url", "origin", new_url]) {
+        command_line::throw_error(format!("Failed to point origin to '{}', set it by hand: {}", new_url, error).as_str());
+    }
+
+    if let Err(error) = fs::rename(repoPath, &new_path) {
+        command_line::throw_error(format!("Failed to rename the folder to '{}': {}", new_path.display(), error).as_str());
+        return Some(repoPath.to_string());
+    }
+    println!("{} '{}' to '{}'", "Moved".green().bold(), repoPath, new_path.display());
+    Some(new_path.to_string_lossy().to_string())
+}
+
+fn full_name(repoPath: &str, user_config: &Config) -> (String, String) {
+    let repoName = Path::new(repoPath).file_name().and_then(|name| name.to_str()).unwrap_or(repoPath);
+    remote_full_name(Some(repoPath), repoName, user_config)
+}
@@ -0,0 +1,259 @@
+use crate::*;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// lives next to config.json
+const TRASH_PATH: &str = "./trash";
+const INDEX_FILE: &str = "index.json";
+
+#[derive(Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub id: u32,
+    pub name: String,
+    pub original_path: String,
+    pub deleted_at: u64, // unix timestamp
+    pub size: u64,       // bytes
+}
+
+fn index_path() -> PathBuf {
+    Path::new(TRASH_PATH).join(INDEX_FILE)
+}
+
+/// A missing index is an empty trash. An unreadable one is an error, writing over it would lose every entry.
+fn read_index() -> Result<Vec<TrashEntry>, String> {
+    let file = match File::open(index_path()) {
+        Ok(file) => file,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(format!("Failed to read the trash index '{}': {}", index_path().display(), error)),
+    };
+    serde_json::from_reader(file)
+        .map_err(|error| format!("The trash index '{}' is corrupt, fix or remove it by hand: {}", index_path().display(), error))
+}
+
+fn write_index(entries: &[TrashEntry]) -> io::Result<()> {
+    ensure_path_exists(TRASH_PATH)?;
+    let json = serde_json::to_string_pretty(entries)?;
+    fs::write(index_path(), json)
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_secs()).unwrap_or(0)
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let mut size = 0;
+    if let Ok(entries) = fs::read_dir(path) {
+        for entry in entries.flatten() {
+            match entry.metadata() {
+                Ok(metadata) if metadata.is_dir() => size += dir_size(&entry.path()),
+                Ok(metadata) => size += metadata.len(),
+                Err(_) => {}
+            }
+        }
+    }
+    size
+}
+
+/// Renames the folder, or copies and deletes it if it lives on another file system.
+/// On an error nothing was moved. If only the source can't be fully deleted after copying,
+/// the copy is complete and counts as moved.
+fn move_dir(from: &Path, to: &Path) -> io::Result<()> {
+    if to.exists() {
+        return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("'{}' already exists", to.display())));
+    }
+    match fs::rename(from, to) {
+        Ok(()) => return Ok(()),
+        Err(error) if error.kind() == io::ErrorKind::CrossesDevices => {}
+        Err(error) => return Err(error),
+    }
+
+    if let Err(error) = copy_dir(from, to) {
+        let _ = fs::remove_dir_all(to);
+        return Err(error);
+    }
+    if let Err(error) = fs::remove_dir_all(from) {
+        command_line::throw_error(format!("Copied to '{}', but '{}' could not be fully removed: {}", to.display(), from.display(), error).as_str());
+    }
+    Ok(())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        }
+        else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+fn format_size(size: u64) -> String {
+    let units = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = size as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < units.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", size, units[unit])
+    }
+    else {
+        format!("{:.1} {}", size, units[unit])
+    }
+}
+
+fn format_age(timestamp: u64) -> String {
+    let seconds = now().saturating_sub(timestamp);
+    match seconds {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{} min ago", seconds / 60),
+        3600..=86399 => format!("{} h ago", seconds / 3600),
+        _ => format!("{} days ago", seconds / 86400),
+    }
+}
+
+/// Moves a folder into the trash instead of deleting it. Returns the id to restore it with.
+pub fn move_to_trash(path: &str) -> Result<u32, String> {
+    let source = Path::new(path);
+    if !source.is_dir() {
+        return Err(format!("'{}' is not a folder", path));
+    }
+    let original_path = fs::canonicalize(source).map_err(|error| error.to_string())?;
+
+    let mut entries = read_index()?;
+    // folders of emptied or lost entries can still be there
+    let mut id = entries.iter().map(|entry| entry.id).max().unwrap_or(0) + 1;
+    while Path::new(TRASH_PATH).join(id.to_string()).exists() {
+        id += 1;
+    }
+    let size = dir_size(source);
+
+    let target = Path::new(TRASH_PATH).join(id.to_string());
+    ensure_path_exists(TRASH_PATH).map_err(|error| error.to_string())?;
+    move_dir(source, &target).map_err(|error| error.to_string())?;
+
+    entries.push(TrashEntry {
+        id,
+        name: source.file_name().and_then(|name| name.to_str()).unwrap_or(path).to_string(),
+        original_path: original_path.to_string_lossy().into_owned(),
+        deleted_at: now(),
+        size,
+    });
+    // a folder without an index entry could never be restored, so it goes back
+    if let Err(error) = write_index(&entries) {
+        return match move_dir(&target, source) {
+            Ok(()) => Err(format!("Failed to update the trash index, '{}' was left in place: {}", path, error)),
+            Err(move_error) => Err(format!("Failed to update the trash index ({}) and to move '{}' back, it is in '{}': {}", error, path, target.display(), move_error)),
+        };
+    }
+    Ok(id)
+}
+
+pub fn list() {
+    let entries = match read_index() {
+        Ok(entries) => entries,
+        Err(error) => {
+            command_line::throw_error(error.as_str());
+            return;
+        }
+    };
+    if entries.is_empty() {
+        println!("The trash is empty");
+        return;
+    }
+
+    let total: u64 = entries.iter().map(|entry| entry.size).sum();
+    for entry in &entries {
+        println!(
+            "{}  {}  {}  {}",
+            format!("[{}]", entry.id).yellow().bold(),
+            entry.name.blue().bold(),
+            entry.original_path.italic(),
+            format!("({}, {})", format_size(entry.size), format_age(entry.deleted_at)).white(),
+        );
+    }
+    println!("{} folder(s), {} in total", entries.len(), format_size(total));
+}
+
+pub fn restore(id: u32) {
+    let mut entries = match read_index() {
+        Ok(entries) => entries,
+        Err(error) => {
+            command_line::throw_error(error.as_str());
+            return;
+        }
+    };
+    let position = match entries.iter().position(|entry| entry.id == id) {
+        Some(position) => position,
+        None => {
+            command_line::throw_error(format!("Nothing with id {} in the trash", id).as_str());
+            return;
+        }
+    };
+
+    let original_path = PathBuf::from(&entries[position].original_path);
+    if original_path.exists() {
+        command_line::throw_error(format!("'{}' already exists, move it away first", original_path.display()).as_str());
+        return;
+    }
+    if let Some(parent) = original_path.parent() {
+        if let Err(error) = ensure_path_exists(parent) {
+            command_line::throw_error(format!("Failed to create '{}': {}", parent.display(), error).as_str());
+            return;
+        }
+    }
+
+    if let Err(error) = move_dir(&Path::new(TRASH_PATH).join(id.to_string()), &original_path) {
+        command_line::throw_error(format!("Failed to restore '{}': {}", original_path.display(), error).as_str());
+        return;
+    }
+    entries.remove(position);
+    if let Err(error) = write_index(&entries) {
+        command_line::throw_error(format!("Failed to update the trash index: {}", error).as_str());
+    }
+    println!("{} '{}'", "Restored".green().bold(), original_path.display());
+}
+
+/// The only place where folders are deleted for good.
+pub fn empty() {
+    let entries = match read_index() {
+        Ok(entries) => entries,
+        Err(error) => {
+            command_line::throw_error(error.as_str());
+            return;
+        }
+    };
+    if entries.is_empty() {
+        println!("The trash is already empty");
+        return;
+    }
+
+    let total: u64 = entries.iter().map(|entry| entry.size).sum();
+    if !command_line::confirm(format!("Permanently delete {} folder(s) ({})?", entries.len(), format_size(total)).as_str()) {
+        return;
+    }
+
+    let mut kept = Vec::new();
+    for entry in entries {
+        let path = Path::new(TRASH_PATH).join(entry.id.to_string());
+        match fs::remove_dir_all(&path) {
+            Ok(_) => {}
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+            Err(error) => {
+                command_line::throw_error(format!("Failed to delete '{}': {}", entry.name, error).as_str());
+                kept.push(entry);
+            }
+        }
+    }
+    if let Err(error) = write_index(&kept) {
+        command_line::throw_error(format!("Failed to update the trash index: {}", error).as_str());
+    }
+    if kept.is_empty() {
+        println!("{} the trash", "Emptied".green().bold());
+    }
+}